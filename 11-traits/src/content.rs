@@ -1,18 +1,27 @@
+pub mod feeds;
 mod news_articles;
+pub mod threads;
 mod tweets;
 
-use aggregator::Summary;
+use aggregator::{Dispatcher, Summary, WriteNotifier};
 pub use news_articles::NewsArticle;
 pub use tweets::Tweet;
 
 // implTrait
 pub fn notify_impl(item: &impl Summary) {
-  println!("Breaking news: {}", item.summarize());
+  breaking_news(item);
 }
 // Trait Bound Syntax: The impl Trait syntax works for straightforward cases but is
 // actually syntax sugar for a longer form known as a trait bound; it looks like this:
 pub fn notify_trait_bound<T: Summary>(item: &T) {
-  println!("Breaking news: {}", item.summarize());
+  breaking_news(item);
+}
+
+// Both of them hand the item to a dispatcher, with stdout as its only sink.
+fn breaking_news<T: Summary>(item: &T) {
+  let mut dispatcher = Dispatcher::new();
+  dispatcher.subscribe(WriteNotifier::stdout());
+  dispatcher.dispatch(item).expect("couldn't write to stdout");
 }
 
 // This longer form is equivalent to the example in the previous section
@@ -66,9 +75,13 @@ pub fn returns_summarizable() -> impl Summary {
   // We’ll cover how to write a function with this behavior in the “Using Trait
   // Objects That Allow for Values of Different Types” section of Chapter 17.
   Tweet {
+    id: String::from("1"),
     username: String::from("horse_ebooks"),
     content: String::from("of course, as you probably already know, people"),
+    posted_at: "2012-05-31T20:13:00Z".parse().unwrap(),
     reply: false,
     retweet: false,
+    parent_id: None,
+    original_id: None,
   }
 }
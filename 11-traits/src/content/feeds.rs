@@ -0,0 +1,516 @@
+// Reading news feeds into NewsArticles. Both RSS 2.0 (`<rss><channel><item>`) and Atom
+// (`<feed><entry>`) are understood, and which one a document is comes from its root element.
+//
+// A feed that isn't XML, or isn't a feed at all, can't be read, but a single entry that lacks
+// what an article needs only spoils that entry: the feed comes back with an error in its place
+// and the other entries as articles.
+use super::NewsArticle;
+use aggregator::{DateTime, Utc};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+  Rss,
+  Atom,
+}
+
+#[derive(Debug)]
+pub enum FeedError {
+  Io(io::Error),
+  Xml(quick_xml::Error),
+  // the root element, which is neither `rss` nor `feed`
+  NotAFeed(String),
+}
+
+impl fmt::Display for FeedError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      FeedError::Io(err) => write!(f, "couldn't read the feed: {}", err),
+      FeedError::Xml(err) => write!(f, "the feed isn't valid XML: {}", err),
+      FeedError::NotAFeed(root) => write!(f, "<{}> is neither an RSS nor an Atom feed", root),
+    }
+  }
+}
+
+impl std::error::Error for FeedError {}
+
+impl From<io::Error> for FeedError {
+  fn from(err: io::Error) -> FeedError {
+    FeedError::Io(err)
+  }
+}
+
+impl From<quick_xml::Error> for FeedError {
+  fn from(err: quick_xml::Error) -> FeedError {
+    FeedError::Xml(err)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+  MissingId,
+  MissingTitle,
+  MissingAuthor,
+  MissingContent,
+  MissingDate,
+  // a date that's neither as RSS nor as Atom writes them
+  BadDate(String),
+  // text in `element` with an entity that couldn't be resolved
+  BadText { element: String, message: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemError {
+  // which item or entry of the feed it is, counting from 0
+  pub index: usize,
+  pub problem: Problem,
+}
+
+impl fmt::Display for ItemError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "entry {}: ", self.index)?;
+    match &self.problem {
+      Problem::MissingId => write!(f, "no id"),
+      Problem::MissingTitle => write!(f, "no title"),
+      Problem::MissingAuthor => write!(f, "no author, and the feed doesn't have one either"),
+      Problem::MissingContent => write!(f, "no content"),
+      Problem::MissingDate => write!(f, "no date"),
+      Problem::BadDate(date) => write!(f, "{:?} isn't a date", date),
+      Problem::BadText { element, message } => write!(f, "bad text in <{}>: {}", element, message),
+    }
+  }
+}
+
+impl std::error::Error for ItemError {}
+
+#[derive(Debug)]
+pub struct Feed {
+  pub format: Format,
+  // one for each item or entry, in the order of the feed
+  pub items: Vec<Result<NewsArticle, ItemError>>,
+}
+
+impl Feed {
+  pub fn articles(&self) -> impl Iterator<Item = &NewsArticle> {
+    self.items.iter().filter_map(|item| item.as_ref().ok())
+  }
+
+  pub fn errors(&self) -> impl Iterator<Item = &ItemError> {
+    self.items.iter().filter_map(|item| item.as_ref().err())
+  }
+}
+
+pub fn read_file(path: impl AsRef<Path>) -> Result<Feed, FeedError> {
+  read(BufReader::new(File::open(path)?))
+}
+
+pub fn read(reader: impl BufRead) -> Result<Feed, FeedError> {
+  let root = Element::parse(reader)?;
+  match root.name.as_str() {
+    "rss" => {
+      let channel = root.child("channel");
+      let default_author = channel
+        .and_then(|c| c.text_of("managingEditor"))
+        .map(rss_author);
+      let items = channel
+        .map(|c| c.children_named("item").collect())
+        .unwrap_or_default();
+      Ok(Feed {
+        format: Format::Rss,
+        items: articles(items, default_author, rss_article),
+      })
+    }
+    "feed" => {
+      let default_author = root.child("author").and_then(|a| a.text_of("name"));
+      let entries = root.children_named("entry").collect();
+      Ok(Feed {
+        format: Format::Atom,
+        items: articles(entries, default_author, atom_article),
+      })
+    }
+    other => Err(FeedError::NotAFeed(other.to_string())),
+  }
+}
+
+// What an item has to say, before it's known to make an article.
+struct Fields {
+  id: Option<String>,
+  headline: Option<String>,
+  author: Option<String>,
+  content: Option<String>,
+  location: Option<String>,
+  posted_at: Option<String>,
+}
+
+fn articles(
+  items: Vec<&Element>,
+  default_author: Option<String>,
+  fields: fn(&Element) -> Fields,
+) -> Vec<Result<NewsArticle, ItemError>> {
+  items
+    .into_iter()
+    .enumerate()
+    .map(|(index, item)| {
+      let error = |problem| ItemError { index, problem };
+      if let Some((element, message)) = item.bad_text() {
+        return Err(error(Problem::BadText { element, message }));
+      }
+      let fields = fields(item);
+      Ok(NewsArticle {
+        id: fields.id.ok_or(error(Problem::MissingId))?,
+        headline: fields.headline.ok_or(error(Problem::MissingTitle))?,
+        author: fields
+          .author
+          .or_else(|| default_author.clone())
+          .ok_or(error(Problem::MissingAuthor))?,
+        content: fields.content.ok_or(error(Problem::MissingContent))?,
+        location: fields.location.unwrap_or_default(),
+        posted_at: match fields.posted_at {
+          Some(date) => parse_date(&date).ok_or(error(Problem::BadDate(date)))?,
+          None => return Err(error(Problem::MissingDate)),
+        },
+      })
+    })
+    .collect()
+}
+
+fn rss_article(item: &Element) -> Fields {
+  Fields {
+    id: item.text_of("guid").or_else(|| item.text_of("link")),
+    headline: item.text_of("title"),
+    author: item
+      .text_of("dc:creator")
+      .or_else(|| item.text_of("author").map(rss_author)),
+    content: item
+      .text_of("content:encoded")
+      .or_else(|| item.text_of("description")),
+    location: location(item),
+    posted_at: item.text_of("pubDate"),
+  }
+}
+
+fn atom_article(entry: &Element) -> Fields {
+  Fields {
+    id: entry.text_of("id"),
+    headline: entry.text_of("title"),
+    author: entry.child("author").and_then(|a| a.text_of("name")),
+    content: entry
+      .text_of("content")
+      .or_else(|| entry.text_of("summary")),
+    location: location(entry),
+    posted_at: entry
+      .text_of("published")
+      .or_else(|| entry.text_of("updated")),
+  }
+}
+
+// RSS dates are as in `Thu, 21 Oct 2021 12:00:00 +0200`, and Atom's as in
+// `2021-10-21T12:00:00+02:00`, but either may turn up in the other.
+fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+  DateTime::parse_from_rfc2822(date)
+    .or_else(|_| DateTime::parse_from_rfc3339(date))
+    .ok()
+    .map(|date| date.with_timezone(&Utc))
+}
+
+// RSS wants an email address for the author, as in `jane@example.com (Jane Doe)`, where the
+// name is what we're after. Anything else is taken as it is.
+fn rss_author(author: String) -> String {
+  match (author.find('('), author.rfind(')')) {
+    (Some(open), Some(close)) if open < close && author.contains('@') => {
+      author[open + 1..close].trim().to_string()
+    }
+    _ => author,
+  }
+}
+
+// Neither format has a place for where the news is from, but feeds often say so with GeoRSS or
+// Dublin Core. Without either, the location is left empty.
+fn location(item: &Element) -> Option<String> {
+  item
+    .text_of("georss:featurename")
+    .or_else(|| item.text_of("dc:coverage"))
+}
+
+// Just enough of an XML document to pick the articles out of: the names of the elements, as
+// they're written with their prefix, and their text.
+#[derive(Debug, Default)]
+struct Element {
+  name: String,
+  text: String,
+  children: Vec<Element>,
+  // why some of the text couldn't be read, which spoils the item it's in rather than the feed
+  bad_text: Option<String>,
+}
+
+impl Element {
+  fn parse(reader: impl BufRead) -> Result<Element, FeedError> {
+    let mut reader = Reader::from_reader(reader);
+    reader.config_mut().trim_text(true);
+    let mut buf = Vec::new();
+    // the elements that are open, the root first
+    let mut open: Vec<Element> = Vec::new();
+    loop {
+      match reader.read_event_into(&mut buf)? {
+        Event::Start(e) => open.push(Element::named(e.name().as_ref())),
+        Event::Empty(e) => {
+          let element = Element::named(e.name().as_ref());
+          match open.last_mut() {
+            Some(parent) => parent.children.push(element),
+            None => return Ok(element),
+          }
+        }
+        Event::End(_) => {
+          // the reader has already checked that this closes the last one
+          let element = open.pop().unwrap();
+          match open.last_mut() {
+            Some(parent) => parent.children.push(element),
+            None => return Ok(element),
+          }
+        }
+        Event::Text(e) => {
+          if let Some(element) = open.last_mut() {
+            match e.unescape() {
+              Ok(text) => element.push_text(&text),
+              Err(err) => element.bad_text = Some(err.to_string()),
+            }
+          }
+        }
+        Event::CData(e) => {
+          if let Some(element) = open.last_mut() {
+            element.push_text(&String::from_utf8_lossy(&e.into_inner()));
+          }
+        }
+        Event::Eof => {
+          let message = match open.first() {
+            Some(root) => format!("<{}> isn't closed", root.name),
+            None => "there's no root element".to_string(),
+          };
+          return Err(FeedError::Xml(
+            io::Error::new(io::ErrorKind::UnexpectedEof, message).into(),
+          ));
+        }
+        _ => {}
+      }
+      buf.clear();
+    }
+  }
+
+  fn named(name: &[u8]) -> Element {
+    Element {
+      name: String::from_utf8_lossy(name).into_owned(),
+      ..Element::default()
+    }
+  }
+
+  fn push_text(&mut self, text: &str) {
+    if !self.text.is_empty() {
+      self.text.push(' ');
+    }
+    self.text.push_str(text);
+  }
+
+  fn child<'a>(&'a self, name: &'a str) -> Option<&'a Element> {
+    self.children_named(name).next()
+  }
+
+  fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+    self.children.iter().filter(move |c| c.name == name)
+  }
+
+  // All the text in the child called `name`, including that of its own children, as in Atom's
+  // XHTML content. Blank text counts as none.
+  fn text_of(&self, name: &str) -> Option<String> {
+    let mut text = String::new();
+    self.child(name)?.collect_text(&mut text);
+    if text.is_empty() {
+      None
+    } else {
+      Some(text)
+    }
+  }
+
+  fn collect_text(&self, text: &mut String) {
+    if !self.text.is_empty() {
+      if !text.is_empty() {
+        text.push(' ');
+      }
+      text.push_str(&self.text);
+    }
+    for child in self.children.iter() {
+      child.collect_text(text);
+    }
+  }
+
+  // The first element in this one with text that couldn't be read, and why.
+  fn bad_text(&self) -> Option<(String, String)> {
+    match &self.bad_text {
+      Some(message) => Some((self.name.clone(), message.clone())),
+      None => self.children.iter().find_map(|c| c.bad_text()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const RSS: &str = r#"<?xml version="1.0"?>
+<rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/"
+     xmlns:georss="http://www.georss.org/georss">
+  <channel>
+    <title>Daily News</title>
+    <managingEditor>desk@example.com (News Desk)</managingEditor>
+    <item>
+      <title>Tram line opens</title>
+      <guid>https://example.com/tram</guid>
+      <pubDate>Thu, 21 Oct 2021 12:00:00 +0200</pubDate>
+      <dc:creator>Ana Horvat</dc:creator>
+      <description>The new line runs &amp; runs.</description>
+      <georss:featurename>Zagreb, Croatia</georss:featurename>
+    </item>
+    <item>
+      <title>Weather</title>
+      <link>https://example.com/weather</link>
+      <pubDate>Thu, 21 Oct 2021 08:00:00 GMT</pubDate>
+      <description><![CDATA[<p>Sunny</p>]]></description>
+    </item>
+    <item>
+      <guid>untitled</guid>
+      <pubDate>Wed, 20 Oct 2021 08:00:00 GMT</pubDate>
+      <dc:creator>Nobody</dc:creator>
+      <description>A story without a title</description>
+    </item>
+    <item>
+      <title>Caf&eacute;s reopen</title>
+      <description>Coffee</description>
+    </item>
+    <item>
+      <title>Tomorrow's news</title>
+      <guid>tomorrow</guid>
+      <pubDate>tomorrow</pubDate>
+      <description>Soon</description>
+    </item>
+  </channel>
+</rss>"#;
+
+  const ATOM: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Example Blog</title>
+  <entry>
+    <title>Traits in Rust</title>
+    <id>urn:example:traits</id>
+    <published>2021-10-21T12:00:00+02:00</published>
+    <updated>2021-10-22T09:00:00Z</updated>
+    <author><name>Tomislav Capan</name></author>
+    <content type="xhtml"><div><p>Traits</p><p>define shared behavior.</p></div></content>
+  </entry>
+  <entry>
+    <title>Lifetimes</title>
+    <id>urn:example:lifetimes</id>
+    <updated>2021-10-22T09:00:00Z</updated>
+    <author><name>Tomislav Capan</name></author>
+    <summary>Coming soon</summary>
+  </entry>
+  <entry>
+    <title>Untitled draft</title>
+    <id>urn:example:draft</id>
+    <updated>2021-10-23T09:00:00Z</updated>
+    <author><name>Tomislav Capan</name></author>
+  </entry>
+</feed>"#;
+
+  #[test]
+  fn reads_rss_items() {
+    let feed = read(RSS.as_bytes()).unwrap();
+    assert_eq!(Format::Rss, feed.format);
+    assert_eq!(5, feed.items.len());
+
+    let articles: Vec<&NewsArticle> = feed.articles().collect();
+    assert_eq!(2, articles.len());
+    assert_eq!("https://example.com/tram", articles[0].id);
+    assert_eq!("Tram line opens", articles[0].headline);
+    assert_eq!("Ana Horvat", articles[0].author);
+    assert_eq!("The new line runs & runs.", articles[0].content);
+    assert_eq!("Zagreb, Croatia", articles[0].location);
+    assert_eq!(
+      "2021-10-21T10:00:00+00:00",
+      articles[0].posted_at.to_rfc3339()
+    );
+    // the channel's editor stands in for the item's author, and there's no location to be had
+    assert_eq!("News Desk", articles[1].author);
+    assert_eq!("<p>Sunny</p>", articles[1].content);
+    assert_eq!("", articles[1].location);
+    // without a guid, the link is what tells items apart
+    assert_eq!("https://example.com/weather", articles[1].id);
+  }
+
+  #[test]
+  fn bad_items_dont_spoil_the_feed() {
+    let feed = read(RSS.as_bytes()).unwrap();
+    let errors: Vec<String> = feed.errors().map(|e| e.to_string()).collect();
+    assert_eq!(3, errors.len());
+    assert_eq!("entry 2: no title", errors[0]);
+    assert!(errors[1].starts_with("entry 3: bad text in <title>"));
+    assert_eq!("entry 4: \"tomorrow\" isn't a date", errors[2]);
+  }
+
+  #[test]
+  fn reads_atom_entries() {
+    let feed = read(ATOM.as_bytes()).unwrap();
+    assert_eq!(Format::Atom, feed.format);
+    let traits = feed.items[0].as_ref().unwrap();
+    assert_eq!("Traits in Rust", traits.headline);
+    assert_eq!("Tomislav Capan", traits.author);
+    assert_eq!("Traits define shared behavior.", traits.content);
+    // when it was first published rather than updated
+    assert_eq!("urn:example:traits", traits.id);
+    assert_eq!("2021-10-21T10:00:00+00:00", traits.posted_at.to_rfc3339());
+    assert_eq!("Coming soon", feed.items[1].as_ref().unwrap().content);
+    assert_eq!(
+      Some(&ItemError {
+        index: 2,
+        problem: Problem::MissingContent
+      }),
+      feed.items[2].as_ref().err()
+    );
+  }
+
+  #[test]
+  fn rss_authors_are_names_rather_than_addresses() {
+    assert_eq!(
+      "Jane Doe",
+      rss_author("jane@example.com (Jane Doe)".to_string())
+    );
+    assert_eq!("Jane (JD) Doe", rss_author("Jane (JD) Doe".to_string()));
+    assert_eq!(
+      "jane@example.com",
+      rss_author("jane@example.com".to_string())
+    );
+  }
+
+  #[test]
+  fn documents_that_arent_feeds_are_errors() {
+    assert!(matches!(
+      read("<html><body/></html>".as_bytes()),
+      Err(FeedError::NotAFeed(root)) if root == "html"
+    ));
+    assert!(matches!(
+      read("<rss><channel></rss>".as_bytes()),
+      Err(FeedError::Xml(_))
+    ));
+    assert!(matches!(
+      read("<rss><channel>".as_bytes()),
+      Err(FeedError::Xml(_))
+    ));
+    assert!(matches!(
+      read_file("feeds/missing.rss"),
+      Err(FeedError::Io(_))
+    ));
+  }
+}
@@ -1,10 +1,13 @@
-use aggregator::Summary;
+use aggregator::{DateTime, Summary, Timestamped, Utc};
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct NewsArticle {
+  pub id: String,
   pub headline: String,
   pub location: String,
   pub author: String,
   pub content: String,
+  pub posted_at: DateTime<Utc>,
 }
 
 impl Summary for NewsArticle {
@@ -18,6 +21,20 @@ impl Summary for NewsArticle {
   }
 
   fn authored_by(&self) -> String {
-    format!("{}", self.author)
+    self.author.clone()
+  }
+
+  fn content(&self) -> Option<&str> {
+    Some(&self.content)
+  }
+}
+
+impl Timestamped for NewsArticle {
+  fn id(&self) -> String {
+    self.id.clone()
+  }
+
+  fn posted_at(&self) -> DateTime<Utc> {
+    self.posted_at
   }
 }
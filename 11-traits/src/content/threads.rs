@@ -0,0 +1,399 @@
+// Putting conversations back together from loose tweets. A reply goes under the tweet it
+// replies to, and a retweet isn't a tweet of its own in a thread but one more retweet of its
+// original, which is what a reply to a retweet goes under as well.
+//
+// Tweets whose parent isn't among them start threads of their own, as do tweets that, through
+// bad data, reply to each other in a circle: no tweet is ever lost on the way.
+use super::Tweet;
+use aggregator::{DateTime, Summary, Timestamped, Utc};
+use std::collections::{HashMap, HashSet};
+use std::mem;
+
+// Threads can go as deep as people keep replying, so nothing here goes down them by recursion,
+// which would run out of stack long before that: they're walked with a stack of their own. That's
+// why a thread can't be cloned, compared or debug printed, which would all recurse, and since it
+// has a Drop of its own, its fields can't be moved out of it either: the replies can be taken
+// with `mem::take`, and the tweet cloned.
+pub struct Thread {
+  pub tweet: Tweet,
+  // how many times the tweet was retweeted, retweets of retweets included
+  pub retweets: usize,
+  // oldest first
+  pub replies: Vec<Thread>,
+}
+
+impl Thread {
+  // The replies all the way down.
+  pub fn reply_count(&self) -> usize {
+    self.all().count() - 1
+  }
+
+  // The retweets of all the tweets in the thread.
+  pub fn retweet_count(&self) -> usize {
+    self.all().map(|thread| thread.retweets).sum()
+  }
+
+  // This thread and every one under it, in no particular order.
+  fn all(&self) -> impl Iterator<Item = &Thread> {
+    let mut pending = vec![self];
+    std::iter::from_fn(move || {
+      let thread = pending.pop()?;
+      pending.extend(thread.replies.iter());
+      Some(thread)
+    })
+  }
+}
+
+// Dropping the replies one by one would go down the thread the same way.
+impl Drop for Thread {
+  fn drop(&mut self) {
+    let mut pending = mem::take(&mut self.replies);
+    while let Some(mut thread) = pending.pop() {
+      pending.append(&mut thread.replies);
+    }
+  }
+}
+
+impl Summary for Thread {
+  fn authored_by(&self) -> String {
+    self.tweet.authored_by()
+  }
+
+  fn summarize(&self) -> String {
+    format!(
+      "{} ({}, {})",
+      self.tweet.summarize(),
+      counted(self.reply_count(), "reply", "replies"),
+      counted(self.retweet_count(), "retweet", "retweets")
+    )
+  }
+
+  // a thread can start with a reply to a tweet that isn't in it
+  fn is_reply(&self) -> bool {
+    self.tweet.is_reply()
+  }
+}
+
+impl Timestamped for Thread {
+  fn id(&self) -> String {
+    self.tweet.id()
+  }
+
+  fn posted_at(&self) -> DateTime<Utc> {
+    self.tweet.posted_at()
+  }
+}
+
+fn counted(n: usize, one: &str, many: &str) -> String {
+  match n {
+    1 => format!("1 {}", one),
+    n => format!("{} {}", n, many),
+  }
+}
+
+#[derive(Debug, Default)]
+pub struct ThreadBuilder {
+  tweets: Vec<Tweet>,
+}
+
+impl ThreadBuilder {
+  pub fn new() -> ThreadBuilder {
+    ThreadBuilder::default()
+  }
+
+  pub fn add(&mut self, tweet: Tweet) {
+    self.tweets.push(tweet);
+  }
+
+  // The threads, the oldest first, and then those of any circles of replies. A tweet that's
+  // been added more than once is only in them the first time.
+  pub fn build(self) -> Vec<Thread> {
+    let mut tweets = self.tweets;
+    tweets.sort_by_key(|tweet| tweet.posted_at);
+    let mut order = Vec::new();
+    let mut by_id = HashMap::new();
+    for tweet in tweets {
+      if !by_id.contains_key(&tweet.id) {
+        order.push(tweet.id.clone());
+        by_id.insert(tweet.id.clone(), tweet);
+      }
+    }
+
+    let originals = Originals { by_id: &by_id };
+    let mut retweets: HashMap<String, usize> = HashMap::new();
+    let mut collapsed = HashSet::new();
+    for id in order.iter() {
+      let original = originals.of(id);
+      if original != id {
+        *retweets.entry(original.to_string()).or_default() += 1;
+        collapsed.insert(id.clone());
+      }
+    }
+
+    let mut roots = Vec::new();
+    let mut children: HashMap<String, Vec<String>> = HashMap::new();
+    for id in order.iter().filter(|id| !collapsed.contains(*id)) {
+      let parent = by_id[id]
+        .parent_id
+        .as_deref()
+        .filter(|parent| by_id.contains_key(*parent))
+        .map(|parent| originals.of(parent));
+      match parent {
+        Some(parent) if parent != id => children
+          .entry(parent.to_string())
+          .or_default()
+          .push(id.clone()),
+        _ => roots.push(id.clone()),
+      }
+    }
+
+    let mut tweets = by_id;
+    for id in collapsed.iter() {
+      tweets.remove(id);
+    }
+    let mut assembly = Assembly {
+      tweets,
+      children,
+      retweets,
+    };
+    let mut threads: Vec<Thread> = roots.iter().filter_map(|id| assembly.thread(id)).collect();
+    // what's still there after the roots have taken theirs is in a circle
+    threads.extend(order.iter().filter_map(|id| assembly.thread(id)));
+    threads
+  }
+}
+
+// Finds what retweets retweet.
+struct Originals<'a> {
+  by_id: &'a HashMap<String, Tweet>,
+}
+
+impl<'a> Originals<'a> {
+  // The tweet that's retweeted by `id`, through any retweets of retweets, or `id` itself when
+  // it's not a retweet of a tweet that's here. Retweets that go round in a circle are all
+  // retweets of the oldest of them.
+  fn of(&self, id: &str) -> &'a str {
+    let mut chain = vec![self.key(id)];
+    loop {
+      let tweet = &self.by_id[*chain.last().unwrap()];
+      match tweet.original_id.as_deref() {
+        Some(original) if tweet.retweet && self.by_id.contains_key(original) => {
+          let original = self.key(original);
+          if let Some(start) = chain.iter().position(|&id| id == original) {
+            return chain[start..]
+              .iter()
+              .copied()
+              .min_by_key(|&id| (self.by_id[id].posted_at, id))
+              .unwrap();
+          }
+          chain.push(original);
+        }
+        _ => return chain.last().unwrap(),
+      }
+    }
+  }
+
+  fn key(&self, id: &str) -> &'a str {
+    self.by_id.get_key_value(id).unwrap().0
+  }
+}
+
+// The tweets still to be put in a thread, and where they go.
+struct Assembly {
+  tweets: HashMap<String, Tweet>,
+  children: HashMap<String, Vec<String>>,
+  retweets: HashMap<String, usize>,
+}
+
+impl Assembly {
+  // The thread that starts with `id`, unless it's already in one. The tweets are taken out on
+  // the way down, in the order the recursive way would take them, and the threads are put
+  // together on the way back up, the replies to a tweet before the thread it starts.
+  fn thread(&mut self, id: &str) -> Option<Thread> {
+    // the tweets taken, with the place in here of the one they reply to
+    let mut taken: Vec<(String, Tweet, Option<usize>)> = Vec::new();
+    let mut pending = vec![(id.to_string(), None)];
+    while let Some((id, parent)) = pending.pop() {
+      if let Some(tweet) = self.tweets.remove(&id) {
+        let replies = self
+          .children
+          .get(&id)
+          .map_or(&[][..], |replies| &replies[..]);
+        // backwards, so that the oldest reply is taken first
+        pending.extend(
+          replies
+            .iter()
+            .rev()
+            .map(|reply| (reply.clone(), Some(taken.len()))),
+        );
+        taken.push((id, tweet, parent));
+      }
+    }
+
+    let mut replies: Vec<Vec<Thread>> = taken.iter().map(|_| Vec::new()).collect();
+    let mut thread = None;
+    while let Some((id, tweet, parent)) = taken.pop() {
+      // the replies came back newest first
+      let mut own = mem::take(&mut replies[taken.len()]);
+      own.reverse();
+      let built = Thread {
+        tweet,
+        retweets: self.retweets.get(&id).copied().unwrap_or(0),
+        replies: own,
+      };
+      match parent {
+        Some(parent) => replies[parent].push(built),
+        None => thread = Some(built),
+      }
+    }
+    thread
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn tweet(id: &str, username: &str, minute: u32) -> Tweet {
+    Tweet {
+      id: id.to_string(),
+      username: username.to_string(),
+      content: format!("tweet {}", id),
+      posted_at: format!("2021-10-21T12:{:02}:00Z", minute).parse().unwrap(),
+      reply: false,
+      retweet: false,
+      parent_id: None,
+      original_id: None,
+    }
+  }
+
+  fn reply(id: &str, username: &str, minute: u32, parent: &str) -> Tweet {
+    Tweet {
+      reply: true,
+      parent_id: Some(parent.to_string()),
+      ..tweet(id, username, minute)
+    }
+  }
+
+  fn retweet(id: &str, username: &str, minute: u32, original: &str) -> Tweet {
+    Tweet {
+      retweet: true,
+      original_id: Some(original.to_string()),
+      ..tweet(id, username, minute)
+    }
+  }
+
+  fn build(tweets: Vec<Tweet>) -> Vec<Thread> {
+    let mut builder = ThreadBuilder::new();
+    for tweet in tweets {
+      builder.add(tweet);
+    }
+    builder.build()
+  }
+
+  // The ids of a thread as in `a(b(c) d)`.
+  fn shape(thread: &Thread) -> String {
+    if thread.replies.is_empty() {
+      return thread.tweet.id.clone();
+    }
+    let replies: Vec<String> = thread.replies.iter().map(shape).collect();
+    format!("{}({})", thread.tweet.id, replies.join(" "))
+  }
+
+  #[test]
+  fn replies_go_under_their_parents() {
+    // in no particular order, as they'd come from a timeline
+    let threads = build(vec![
+      reply("c", "carl", 3, "b"),
+      tweet("x", "xena", 0),
+      reply("d", "dora", 4, "a"),
+      tweet("a", "ana", 1),
+      reply("b", "bob", 2, "a"),
+    ]);
+    let shapes: Vec<String> = threads.iter().map(shape).collect();
+    assert_eq!(vec!["x", "a(b(c) d)"], shapes);
+    assert_eq!(3, threads[1].reply_count());
+  }
+
+  #[test]
+  fn retweets_collapse_into_their_originals() {
+    let threads = build(vec![
+      tweet("a", "ana", 1),
+      retweet("r1", "bob", 2, "a"),
+      retweet("r2", "carl", 3, "r1"),
+      // replying to a retweet is replying to the original
+      reply("b", "dora", 4, "r2"),
+      retweet("r3", "ana", 5, "b"),
+      // the original of this one isn't here, so it's all there is
+      retweet("r4", "bob", 6, "gone"),
+    ]);
+    let shapes: Vec<String> = threads.iter().map(shape).collect();
+    assert_eq!(vec!["a(b)", "r4"], shapes);
+    assert_eq!(2, threads[0].retweets);
+    assert_eq!(1, threads[0].replies[0].retweets);
+    assert_eq!(3, threads[0].retweet_count());
+  }
+
+  #[test]
+  fn replies_to_missing_tweets_start_threads() {
+    let threads = build(vec![
+      reply("b", "bob", 2, "gone"),
+      reply("c", "carl", 3, "b"),
+    ]);
+    let shapes: Vec<String> = threads.iter().map(shape).collect();
+    assert_eq!(vec!["b(c)"], shapes);
+    assert!(threads[0].is_reply());
+  }
+
+  #[test]
+  fn no_tweet_is_lost() {
+    let threads = build(vec![
+      // a circle of replies, which no tweet starts
+      reply("a", "ana", 1, "c"),
+      reply("b", "bob", 2, "a"),
+      reply("c", "carl", 3, "b"),
+      // a circle of retweets, and a tweet that's been seen twice
+      retweet("r1", "dora", 4, "r2"),
+      retweet("r2", "dora", 5, "r1"),
+      tweet("x", "xena", 6),
+      tweet("x", "xena", 6),
+    ]);
+    let shapes: Vec<String> = threads.iter().map(shape).collect();
+    // the circle of replies comes after the threads that start properly
+    assert_eq!(vec!["r1", "x", "a(b(c))"], shapes);
+    assert_eq!(1, threads[0].retweets);
+  }
+
+  #[test]
+  fn long_conversations_dont_run_out_of_stack() {
+    let length = 100_000;
+    let mut tweets = vec![tweet("0", "ana", 0)];
+    // all at the same time, which doesn't change the order they're in
+    tweets.extend((1..length).map(|i| reply(&i.to_string(), "bob", 0, &(i - 1).to_string())));
+    let threads = build(tweets);
+    assert_eq!(1, threads.len());
+    assert_eq!(length - 1, threads[0].reply_count());
+    assert_eq!(0, threads[0].retweet_count());
+    assert_eq!(
+      format!("@ana: tweet 0 ({} replies, 0 retweets)", length - 1),
+      threads[0].summarize()
+    );
+  }
+
+  #[test]
+  fn threads_summarize_the_conversation() {
+    let threads = build(vec![
+      tweet("a", "ana", 1),
+      reply("b", "bob", 2, "a"),
+      retweet("r1", "carl", 3, "a"),
+    ]);
+    assert_eq!("@ana: tweet a (1 reply, 1 retweet)", threads[0].summarize());
+    assert_eq!("@ana", threads[0].authored_by());
+    assert_eq!("a", threads[0].id());
+    let quiet = build(vec![tweet("q", "ana", 1)]);
+    assert_eq!(
+      "@ana: tweet q (0 replies, 0 retweets)",
+      quiet[0].summarize()
+    );
+  }
+}
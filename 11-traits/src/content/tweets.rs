@@ -1,10 +1,17 @@
-use aggregator::Summary;
+use aggregator::{DateTime, Summary, Timestamped, Utc};
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Tweet {
+  pub id: String,
   pub username: String,
   pub content: String,
+  pub posted_at: DateTime<Utc>,
   pub reply: bool,
   pub retweet: bool,
+  // the tweet this one replies to
+  pub parent_id: Option<String>,
+  // the tweet this one retweets
+  pub original_id: Option<String>,
 }
 
 impl Summary for Tweet {
@@ -15,4 +22,22 @@ impl Summary for Tweet {
   fn authored_by(&self) -> String {
     format!("@{}", self.username)
   }
+
+  fn is_reply(&self) -> bool {
+    self.reply
+  }
+
+  fn is_retweet(&self) -> bool {
+    self.retweet
+  }
+}
+
+impl Timestamped for Tweet {
+  fn id(&self) -> String {
+    self.id.clone()
+  }
+
+  fn posted_at(&self) -> DateTime<Utc> {
+    self.posted_at
+  }
 }
@@ -1,3 +1,14 @@
+mod notify;
+mod options;
+mod timeline;
+
+use std::any::Any;
+
+pub use chrono::{DateTime, Utc};
+pub use notify::{DispatchError, Dispatcher, MemoryNotifier, Notifier, WriteNotifier};
+pub use options::{Ellipsis, SummaryOptions};
+pub use timeline::{Originality, Recency, Score, Timeline};
+
 pub trait Summary {
   // you may provide an interface to implement
   fn authored_by(&self) -> String;
@@ -6,4 +17,41 @@ pub trait Summary {
   fn summarize(&self) -> String {
     format!("(Read more from {}...)", self.authored_by())
   }
+
+  // The summary cut down to what `opts` allows, with an excerpt of the content after it if they
+  // ask for one. The default options don't cut anything, which leaves the summary as it is.
+  fn summarize_with(&self, opts: SummaryOptions) -> String {
+    let mut summary = opts.shorten(&self.summarize());
+    if let (Some(length), Some(content)) = (opts.excerpt, self.content()) {
+      summary.push('\n');
+      summary.push_str(&opts.excerpt_of(content, length));
+    }
+    summary
+  }
+
+  // The text of the item, for the items that have more of it than their summary shows.
+  fn content(&self) -> Option<&str> {
+    None
+  }
+
+  // Whether the item answers another one, or passes another one on. Only tweets do either.
+  fn is_reply(&self) -> bool {
+    false
+  }
+
+  fn is_retweet(&self) -> bool {
+    false
+  }
+}
+
+// What a timeline needs to know about its items on top of their summaries: when they were
+// posted, and what tells them apart. They're Any so that the timeline can tell their types
+// apart too.
+pub trait Timestamped: Summary + Any {
+  // What tells the item apart from any other of its type, even when it comes from more than one
+  // source. Items of different types may well have the same id, as when a feed numbers its
+  // articles the way tweets are numbered.
+  fn id(&self) -> String;
+
+  fn posted_at(&self) -> DateTime<Utc>;
 }
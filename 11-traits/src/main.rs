@@ -1,22 +1,33 @@
 mod content;
 
 // To call the trait methods on instances, the trait and the types must be brought into scope
-use aggregator::Summary;
+use aggregator::{
+  Dispatcher, Originality, Recency, Score, Summary, SummaryOptions, Timeline, Timestamped,
+  WriteNotifier,
+};
+use content::threads::ThreadBuilder;
 use content::{NewsArticle, Tweet};
+use std::cmp::Reverse;
 
 fn main() {
   let tweet = Tweet {
+    id: "1451136436458893315".to_string(),
     username: "tomislavcapan".to_string(),
     content: "Hello, world!".to_string(),
+    posted_at: "2021-10-21T11:00:00Z".parse().unwrap(),
     retweet: false,
     reply: false,
+    parent_id: None,
+    original_id: None,
   };
 
   let news_article = NewsArticle {
+    id: "hello-world".to_string(),
     headline: "Hello, world from our newspaper!".to_string(),
     author: "Tomislav Capan".to_string(),
     content: "This is my world. I want to enjoy in this world.".to_string(),
     location: "Zagreb, Croatia".to_string(),
+    posted_at: "2021-10-21T09:30:00Z".parse().unwrap(),
   };
 
   println!("1 new Tweet:\n{}\n", tweet.summarize());
@@ -28,4 +39,110 @@ fn main() {
   content::notify_trait_bound(&tweet);
   content::notify_trait_bound(&news_article);
   content::notify_trait_bound(&content::returns_summarizable());
+
+  // Articles don't have to be written out by hand, they can come from a feed. Each one is
+  // news to a log file, but only the ones from Zagreb are news here
+  let log = std::env::temp_dir().join("aggregator-news.log");
+  let mut dispatcher = Dispatcher::new().with_options(
+    SummaryOptions::new()
+      .with_max_graphemes(60)
+      .at_word_boundary(),
+  );
+  dispatcher.subscribe_if(WriteNotifier::stdout(), |article: &NewsArticle| {
+    article.location == "Zagreb, Croatia"
+  });
+  match WriteNotifier::append_to(&log) {
+    Ok(file) => dispatcher.subscribe(file),
+    Err(err) => println!("Couldn't open {}: {}", log.display(), err),
+  }
+  let mut sources: Vec<Vec<Box<dyn Timestamped>>> = Vec::new();
+  for name in ["news.rss", "blog.atom"] {
+    let path = format!("{}/feeds/{}", env!("CARGO_MANIFEST_DIR"), name);
+    match content::feeds::read_file(&path) {
+      Ok(feed) => {
+        println!("\nFrom {} ({:?}):", name, feed.format);
+        for article in feed.articles() {
+          println!("{}\n  {}", article.summarize(), article.content);
+        }
+        for article in feed.articles() {
+          if let Err(err) = dispatcher.dispatch(article) {
+            println!("Couldn't pass on {:?}: {}", article.headline, err);
+          }
+        }
+        for error in feed.errors() {
+          println!("Skipped {}", error);
+        }
+        // feeds don't promise any order, but a timeline needs its sources newest first
+        let mut articles: Vec<NewsArticle> = feed.articles().cloned().collect();
+        articles.sort_by_key(|article| Reverse(article.posted_at));
+        sources.push(
+          articles
+            .into_iter()
+            .map(|article| Box::new(article) as Box<dyn Timestamped>)
+            .collect(),
+        );
+      }
+      Err(err) => println!("\nCouldn't read {}: {}", name, err),
+    }
+  }
+
+  // Tweets and articles all go in one timeline
+  let replies = Tweet {
+    id: "1451137000000000000".to_string(),
+    username: "horse_ebooks".to_string(),
+    content: "@tomislavcapan everything happens so much".to_string(),
+    posted_at: "2021-10-21T11:30:00Z".parse().unwrap(),
+    retweet: false,
+    reply: true,
+    parent_id: Some(tweet.id.clone()),
+    original_id: None,
+  };
+  let retweet = Tweet {
+    id: "1451138000000000000".to_string(),
+    username: "rustlang".to_string(),
+    content: tweet.content.clone(),
+    posted_at: "2021-10-21T12:00:00Z".parse().unwrap(),
+    retweet: true,
+    reply: false,
+    parent_id: None,
+    original_id: Some(tweet.id.clone()),
+  };
+
+  // The tweets make a conversation, in which the retweet only counts towards the original
+  let mut threads = ThreadBuilder::new();
+  for t in [&tweet, &replies, &retweet] {
+    threads.add(t.clone());
+  }
+  println!("\nConversations:");
+  for thread in threads.build() {
+    println!("{}", thread.summarize());
+  }
+
+  let tweets: Vec<Box<dyn Timestamped>> = vec![Box::new(replies), Box::new(tweet)];
+  let mut timeline = Timeline::new()
+    .with_source(tweets)
+    .with_source(vec![Box::new(news_article) as Box<dyn Timestamped>]);
+  for source in sources {
+    timeline = timeline.with_source(source);
+  }
+
+  // ranked by how recent they are, with replies and retweets further down
+  let recency = Recency::new(
+    "2021-10-29T00:00:00Z".parse().unwrap(),
+    chrono::Duration::hours(12),
+  );
+  let originality = Originality::default();
+  let score = |item: &dyn Timestamped| recency.score(item) * originality.score(item);
+  // with the summaries cut short enough for a line each
+  let opts = SummaryOptions::new()
+    .with_max_graphemes(48)
+    .at_word_boundary();
+  println!("\nTimeline:");
+  for item in timeline.ranked(&score, 10) {
+    println!(
+      "{} {}",
+      item.posted_at().format("%b %e %H:%M"),
+      item.summarize_with(opts.clone())
+    );
+  }
 }
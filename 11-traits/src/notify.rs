@@ -0,0 +1,399 @@
+// Sending summaries on to wherever people want to hear about them. A Notifier is a sink for
+// the messages, which only deals in text, and a Dispatcher decides which sinks get which items:
+// each sink is subscribed with a filter, so that one can have every item and another only the
+// articles from where its reader lives.
+//
+// The dispatcher is generic over the items it takes, so that filters can look at whatever the
+// items have: a `Dispatcher<NewsArticle>` can filter on the article's location, and a
+// `Dispatcher<dyn Summary>` takes any item but only knows what Summary says about it.
+use crate::{Summary, SummaryOptions};
+use std::cell::RefCell;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::rc::Rc;
+
+pub trait Notifier {
+  fn notify(&mut self, message: &str) -> io::Result<()>;
+}
+
+// Writes each message on a line of its own, which is all the stdout, file and socket sinks
+// need to do. A message that spans lines, as one with an excerpt does, still takes one: its
+// line breaks are written as `\n` and `\r`, and its backslashes doubled so that a `\n` that
+// was in it can be told from one that wasn't.
+pub struct WriteNotifier<W: Write> {
+  out: W,
+}
+
+impl<W: Write> WriteNotifier<W> {
+  pub fn new(out: W) -> WriteNotifier<W> {
+    WriteNotifier { out }
+  }
+}
+
+impl WriteNotifier<io::Stdout> {
+  pub fn stdout() -> WriteNotifier<io::Stdout> {
+    WriteNotifier::new(io::stdout())
+  }
+}
+
+impl WriteNotifier<File> {
+  // Adds the messages to the end of the file, which is created if it isn't there.
+  pub fn append_to(path: impl AsRef<Path>) -> io::Result<WriteNotifier<File>> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    Ok(WriteNotifier::new(file))
+  }
+}
+
+impl WriteNotifier<TcpStream> {
+  pub fn tcp(addr: impl ToSocketAddrs) -> io::Result<WriteNotifier<TcpStream>> {
+    Ok(WriteNotifier::new(TcpStream::connect(addr)?))
+  }
+}
+
+#[cfg(unix)]
+impl WriteNotifier<UnixStream> {
+  pub fn unix(path: impl AsRef<Path>) -> io::Result<WriteNotifier<UnixStream>> {
+    Ok(WriteNotifier::new(UnixStream::connect(path)?))
+  }
+}
+
+impl<W: Write> Notifier for WriteNotifier<W> {
+  fn notify(&mut self, message: &str) -> io::Result<()> {
+    writeln!(self.out, "{}", escaped(message))?;
+    self.out.flush()
+  }
+}
+
+fn escaped(message: &str) -> String {
+  let mut escaped = String::with_capacity(message.len());
+  for c in message.chars() {
+    match c {
+      '\\' => escaped.push_str("\\\\"),
+      '\n' => escaped.push_str("\\n"),
+      '\r' => escaped.push_str("\\r"),
+      c => escaped.push(c),
+    }
+  }
+  escaped
+}
+
+// Keeps the messages, for tests to look at. Clones share them, so one can be kept while the
+// other is given to a dispatcher.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryNotifier {
+  messages: Rc<RefCell<Vec<String>>>,
+}
+
+impl MemoryNotifier {
+  pub fn new() -> MemoryNotifier {
+    MemoryNotifier::default()
+  }
+
+  pub fn messages(&self) -> Vec<String> {
+    self.messages.borrow().clone()
+  }
+}
+
+impl Notifier for MemoryNotifier {
+  fn notify(&mut self, message: &str) -> io::Result<()> {
+    self.messages.borrow_mut().push(message.to_string());
+    Ok(())
+  }
+}
+
+// Whether a sink wants an item.
+type Filter<T> = Box<dyn Fn(&T) -> bool>;
+
+struct Subscription<T: ?Sized> {
+  notifier: Box<dyn Notifier>,
+  filter: Filter<T>,
+}
+
+pub struct Dispatcher<T: ?Sized> {
+  subscriptions: Vec<Subscription<T>>,
+  // how the summaries are cut down for the messages
+  opts: SummaryOptions,
+}
+
+impl<T: Summary + ?Sized> Default for Dispatcher<T> {
+  fn default() -> Dispatcher<T> {
+    Dispatcher::new()
+  }
+}
+
+impl<T: Summary + ?Sized> Dispatcher<T> {
+  pub fn new() -> Dispatcher<T> {
+    Dispatcher {
+      subscriptions: Vec::new(),
+      opts: SummaryOptions::default(),
+    }
+  }
+
+  pub fn with_options(mut self, opts: SummaryOptions) -> Dispatcher<T> {
+    self.opts = opts;
+    self
+  }
+
+  // Subscribes the sink to every item.
+  pub fn subscribe(&mut self, notifier: impl Notifier + 'static) {
+    self.subscribe_if(notifier, |_| true);
+  }
+
+  pub fn subscribe_if(
+    &mut self,
+    notifier: impl Notifier + 'static,
+    filter: impl Fn(&T) -> bool + 'static,
+  ) {
+    self.subscriptions.push(Subscription {
+      notifier: Box::new(notifier),
+      filter: Box::new(filter),
+    });
+  }
+
+  pub fn message(&self, item: &T) -> String {
+    format!("Breaking news: {}", item.summarize_with(self.opts.clone()))
+  }
+
+  // Sends the item to each sink that wants it, and says how many got it. A sink that fails
+  // doesn't keep the item from the ones after it.
+  pub fn dispatch(&mut self, item: &T) -> Result<usize, DispatchError> {
+    let message = self.message(item);
+    let mut delivered = 0;
+    let mut failures = Vec::new();
+    for (i, subscription) in self.subscriptions.iter_mut().enumerate() {
+      if !(subscription.filter)(item) {
+        continue;
+      }
+      match subscription.notifier.notify(&message) {
+        Ok(()) => delivered += 1,
+        Err(err) => failures.push((i, err)),
+      }
+    }
+    if failures.is_empty() {
+      Ok(delivered)
+    } else {
+      Err(DispatchError {
+        delivered,
+        failures,
+      })
+    }
+  }
+}
+
+#[derive(Debug)]
+pub struct DispatchError {
+  // how many sinks got the item all the same
+  pub delivered: usize,
+  // the sinks that didn't, by the order they were subscribed in
+  pub failures: Vec<(usize, io::Error)>,
+}
+
+impl fmt::Display for DispatchError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{} sink(s) failed", self.failures.len())?;
+    for (i, err) in self.failures.iter() {
+      write!(f, "; sink {}: {}", i, err)?;
+    }
+    Ok(())
+  }
+}
+
+impl std::error::Error for DispatchError {}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::{BufRead, BufReader};
+  use std::net::TcpListener;
+  use std::thread;
+
+  struct Article {
+    headline: &'static str,
+    location: &'static str,
+    content: &'static str,
+  }
+
+  impl Summary for Article {
+    fn authored_by(&self) -> String {
+      "Ana".to_string()
+    }
+
+    fn summarize(&self) -> String {
+      format!("{} ({})", self.headline, self.location)
+    }
+
+    fn content(&self) -> Option<&str> {
+      Some(self.content)
+    }
+  }
+
+  const ZAGREB: Article = Article {
+    headline: "Tram line opens",
+    location: "Zagreb",
+    content: "The new line runs from the station to the stadium.",
+  };
+  const SPLIT: Article = Article {
+    headline: "Ferry delayed",
+    location: "Split",
+    content: "High winds kept the ferry to Hvar in port.",
+  };
+
+  // A sink that's gone away.
+  struct Broken;
+
+  impl Notifier for Broken {
+    fn notify(&mut self, _message: &str) -> io::Result<()> {
+      Err(io::Error::new(io::ErrorKind::BrokenPipe, "gone"))
+    }
+  }
+
+  #[test]
+  fn sinks_only_get_what_their_filter_takes() {
+    let everything = MemoryNotifier::new();
+    let zagreb = MemoryNotifier::new();
+    let mut dispatcher = Dispatcher::new();
+    dispatcher.subscribe(everything.clone());
+    dispatcher.subscribe_if(zagreb.clone(), |article: &Article| {
+      article.location == "Zagreb"
+    });
+
+    assert_eq!(2, dispatcher.dispatch(&ZAGREB).unwrap());
+    assert_eq!(1, dispatcher.dispatch(&SPLIT).unwrap());
+    assert_eq!(
+      vec![
+        "Breaking news: Tram line opens (Zagreb)",
+        "Breaking news: Ferry delayed (Split)",
+      ],
+      everything.messages()
+    );
+    assert_eq!(
+      vec!["Breaking news: Tram line opens (Zagreb)"],
+      zagreb.messages()
+    );
+  }
+
+  #[test]
+  fn dispatchers_take_any_summary() {
+    let sink = MemoryNotifier::new();
+    let mut dispatcher: Dispatcher<dyn Summary> =
+      Dispatcher::new().with_options(SummaryOptions::new().with_max_graphemes(10));
+    dispatcher.subscribe(sink.clone());
+    let items: Vec<Box<dyn Summary>> = vec![Box::new(ZAGREB), Box::new(SPLIT)];
+    for item in items.iter() {
+      dispatcher.dispatch(item.as_ref()).unwrap();
+    }
+    assert_eq!(
+      vec!["Breaking news: Tram line…", "Breaking news: Ferry del…"],
+      sink.messages()
+    );
+  }
+
+  #[test]
+  fn failing_sinks_dont_stop_the_others() {
+    let sink = MemoryNotifier::new();
+    let mut dispatcher = Dispatcher::new();
+    dispatcher.subscribe(Broken);
+    dispatcher.subscribe(sink.clone());
+    let err = dispatcher.dispatch(&ZAGREB).unwrap_err();
+    assert_eq!(1, err.delivered);
+    assert_eq!("1 sink(s) failed; sink 0: gone", err.to_string());
+    assert_eq!(1, sink.messages().len());
+  }
+
+  #[test]
+  fn files_are_appended_to() {
+    let path = std::env::temp_dir().join(format!("aggregator-notify-{}.log", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    for article in [ZAGREB, SPLIT] {
+      // a new notifier each time, which mustn't lose what the last one wrote
+      let mut dispatcher = Dispatcher::new();
+      dispatcher.subscribe(WriteNotifier::append_to(&path).unwrap());
+      dispatcher.dispatch(&article).unwrap();
+    }
+    let log = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+      "Breaking news: Tram line opens (Zagreb)\nBreaking news: Ferry delayed (Split)\n",
+      log
+    );
+  }
+
+  // Reads the lines the first client of a listener sends until it hangs up.
+  fn received(stream: impl io::Read) -> Vec<String> {
+    BufReader::new(stream).lines().map(|l| l.unwrap()).collect()
+  }
+
+  #[test]
+  fn messages_go_over_tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = thread::spawn(move || received(listener.accept().unwrap().0));
+
+    let mut dispatcher = Dispatcher::new();
+    dispatcher.subscribe(WriteNotifier::tcp(addr).unwrap());
+    dispatcher.dispatch(&ZAGREB).unwrap();
+    dispatcher.dispatch(&SPLIT).unwrap();
+    drop(dispatcher);
+    assert_eq!(
+      vec![
+        "Breaking news: Tram line opens (Zagreb)",
+        "Breaking news: Ferry delayed (Split)",
+      ],
+      server.join().unwrap()
+    );
+  }
+
+  #[test]
+  fn messages_with_excerpts_stay_on_one_line() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = thread::spawn(move || received(listener.accept().unwrap().0));
+
+    let mut dispatcher = Dispatcher::new().with_options(SummaryOptions::new().with_excerpt(14));
+    dispatcher.subscribe(WriteNotifier::tcp(addr).unwrap());
+    dispatcher.dispatch(&ZAGREB).unwrap();
+    dispatcher.dispatch(&SPLIT).unwrap();
+    drop(dispatcher);
+    assert_eq!(
+      vec![
+        "Breaking news: Tram line opens (Zagreb)\\nThe new line…",
+        "Breaking news: Ferry delayed (Split)\\nHigh winds ke…",
+      ],
+      server.join().unwrap()
+    );
+  }
+
+  #[test]
+  fn backslashes_arent_taken_for_line_breaks() {
+    let mut sink = WriteNotifier::new(Vec::new());
+    sink.notify("C:\\news\r\nmore").unwrap();
+    assert_eq!(
+      "C:\\\\news\\r\\nmore\n",
+      String::from_utf8(sink.out).unwrap()
+    );
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn messages_go_over_unix_sockets() {
+    use std::os::unix::net::UnixListener;
+
+    let path = std::env::temp_dir().join(format!("aggregator-notify-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).unwrap();
+    let server = thread::spawn(move || received(listener.accept().unwrap().0));
+
+    let mut dispatcher = Dispatcher::new();
+    dispatcher.subscribe(WriteNotifier::unix(&path).unwrap());
+    dispatcher.dispatch(&SPLIT).unwrap();
+    drop(dispatcher);
+    let lines = server.join().unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(vec!["Breaking news: Ferry delayed (Split)"], lines);
+  }
+}
@@ -0,0 +1,259 @@
+// How long a summary may get, and how it's cut when it's longer. Lengths are in graphemes,
+// which is what a reader would count as characters: a letter with an accent on it, a flag or
+// an emoji made of several others is one of them, however many code points it takes.
+use unicode_segmentation::UnicodeSegmentation;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Ellipsis {
+  // "…"
+  #[default]
+  Unicode,
+  // "..."
+  Dots,
+  Custom(String),
+  // cut without showing it
+  None,
+}
+
+impl Ellipsis {
+  pub fn as_str(&self) -> &str {
+    match self {
+      Ellipsis::Unicode => "…",
+      Ellipsis::Dots => "...",
+      Ellipsis::Custom(ellipsis) => ellipsis,
+      Ellipsis::None => "",
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SummaryOptions {
+  // the most graphemes a summary may have, the ellipsis included
+  pub max_graphemes: Option<usize>,
+  // what goes where the summary was cut
+  pub ellipsis: Ellipsis,
+  // whether to cut between words rather than in the middle of one
+  pub word_boundary: bool,
+  // how many graphemes of the content to show after the summary
+  pub excerpt: Option<usize>,
+}
+
+impl SummaryOptions {
+  pub fn new() -> SummaryOptions {
+    SummaryOptions::default()
+  }
+
+  pub fn with_max_graphemes(mut self, max: usize) -> SummaryOptions {
+    self.max_graphemes = Some(max);
+    self
+  }
+
+  pub fn with_ellipsis(mut self, ellipsis: Ellipsis) -> SummaryOptions {
+    self.ellipsis = ellipsis;
+    self
+  }
+
+  pub fn at_word_boundary(mut self) -> SummaryOptions {
+    self.word_boundary = true;
+    self
+  }
+
+  pub fn with_excerpt(mut self, length: usize) -> SummaryOptions {
+    self.excerpt = Some(length);
+    self
+  }
+
+  // The summary, cut to `max_graphemes` if it's longer.
+  pub fn shorten(&self, summary: &str) -> String {
+    match self.max_graphemes {
+      Some(max) => self.truncate(summary, max),
+      None => summary.to_string(),
+    }
+  }
+
+  // The start of the content, cut the way summaries are.
+  pub fn excerpt_of(&self, content: &str, length: usize) -> String {
+    self.truncate(content, length)
+  }
+
+  fn truncate(&self, text: &str, max: usize) -> String {
+    if text.graphemes(true).count() <= max {
+      return text.to_string();
+    }
+    // the ellipsis counts towards the length, unless it wouldn't fit at all
+    let ellipsis = match self.ellipsis.as_str() {
+      ellipsis if ellipsis.graphemes(true).count() <= max => ellipsis,
+      _ => "",
+    };
+    let room = max - ellipsis.graphemes(true).count();
+    let end = text
+      .grapheme_indices(true)
+      .nth(room)
+      .map_or(text.len(), |(i, _)| i);
+    let mut kept = &text[..end];
+    if self.word_boundary {
+      // and without the punctuation the last word leaves behind, as in `(` or `,`
+      kept = text[..word_end(text, end)]
+        .trim_end_matches(|c: char| c.is_whitespace() || c.is_ascii_punctuation());
+    }
+    format!("{}{}", kept.trim_end(), ellipsis)
+  }
+}
+
+// Where to cut `text` at `end` or before it so that no word is cut in half: `end` if a word
+// ends there, the start of the word it's in if not. A first word that's too long on its own is
+// cut at `end` anyway, or there'd be nothing left of it.
+fn word_end(text: &str, end: usize) -> usize {
+  let start = text
+    .split_word_bound_indices()
+    .map(|(i, _)| i)
+    .take_while(|&i| i <= end)
+    .last()
+    .unwrap_or(0);
+  match start {
+    0 => end,
+    start => start,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Summary;
+
+  struct Story {
+    headline: &'static str,
+    content: &'static str,
+  }
+
+  impl Summary for Story {
+    fn authored_by(&self) -> String {
+      "Ana".to_string()
+    }
+
+    fn summarize(&self) -> String {
+      self.headline.to_string()
+    }
+
+    fn content(&self) -> Option<&str> {
+      Some(self.content)
+    }
+  }
+
+  fn shorten(max: usize, text: &str) -> String {
+    SummaryOptions::new().with_max_graphemes(max).shorten(text)
+  }
+
+  #[test]
+  fn default_options_leave_the_summary_alone() {
+    let story = Story {
+      headline: "A headline far longer than any notification has room for",
+      content: "",
+    };
+    assert_eq!(
+      story.summarize(),
+      story.summarize_with(SummaryOptions::new())
+    );
+  }
+
+  #[test]
+  fn lengths_are_in_graphemes() {
+    // a family is four people and three zero width joiners, and still one emoji
+    assert_eq!("Family: 👨‍👩‍👧‍👦…", shorten(10, "Family: 👨‍👩‍👧‍👦👨‍👩‍👧‍👦 time"));
+    // flags are pairs of regional indicators, which mustn't be split
+    assert_eq!("🇭🇷🇯🇵…", shorten(3, "🇭🇷🇯🇵🇧🇷🇳🇿"));
+    // an accent that combines with the letter before it stays with it
+    assert_eq!("Cafe\u{301}…", shorten(5, "Cafe\u{301} au lait"));
+    assert_eq!("東京は晴…", shorten(5, "東京は晴れです"));
+    assert_eq!("short", shorten(5, "short"));
+  }
+
+  #[test]
+  fn nothing_is_ever_longer_than_asked() {
+    let texts = [
+      "नमस्ते दुनिया, यह एक लंबा वाक्य है",
+      "مرحبا بالعالم، هذه جملة طويلة",
+      "Ünïcödé ñ with ｆｕｌｌｗｉｄｔｈ letters",
+      "👍🏽👍🏽👍🏽 skin tones 🏳️‍🌈",
+      "Zalgo: Z̷̢̛a̸̡̛l̵̨̛g̴̢̛o̶̧̕",
+    ];
+    let styles = [
+      Ellipsis::Unicode,
+      Ellipsis::Dots,
+      Ellipsis::None,
+      Ellipsis::Custom(" [more]".to_string()),
+    ];
+    for text in texts {
+      for ellipsis in styles.iter() {
+        for word_boundary in [false, true] {
+          for max in 0..12 {
+            let opts = SummaryOptions {
+              max_graphemes: Some(max),
+              ellipsis: ellipsis.clone(),
+              word_boundary,
+              excerpt: None,
+            };
+            let short = opts.shorten(text);
+            assert!(short.graphemes(true).count() <= max, "{:?}", short);
+            // it's the start of the text, with the ellipsis if there's room for it
+            let kept = short.strip_suffix(ellipsis.as_str()).unwrap_or(&short);
+            assert!(text.starts_with(kept), "{:?}", short);
+          }
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn ellipsis_styles() {
+    let opts = |ellipsis| {
+      SummaryOptions::new()
+        .with_max_graphemes(9)
+        .with_ellipsis(ellipsis)
+    };
+    let text = "Breaking news everywhere";
+    assert_eq!("Breaking…", opts(Ellipsis::Unicode).shorten(text));
+    assert_eq!("Breaki...", opts(Ellipsis::Dots).shorten(text));
+    assert_eq!("Breaking", opts(Ellipsis::None).shorten(text));
+    assert_eq!(
+      "Break [+]",
+      opts(Ellipsis::Custom(" [+]".to_string())).shorten(text)
+    );
+    // an ellipsis that leaves no room for the text isn't used at all
+    let long = Ellipsis::Custom(" (continued)".to_string());
+    assert_eq!("Breaking", opts(long).shorten(text));
+  }
+
+  #[test]
+  fn cuts_between_words_when_asked() {
+    let opts = |max| {
+      SummaryOptions::new()
+        .with_max_graphemes(max)
+        .at_word_boundary()
+    };
+    let text = "Traits define shared behavior";
+    assert_eq!("Traits…", opts(12).shorten(text));
+    assert_eq!("Traits define…", opts(15).shorten(text));
+    assert_eq!("Supercal…", opts(9).shorten("Supercalifragilistic words"));
+    assert_eq!("Ça va…", opts(8).shorten("Ça va très bien"));
+    assert_eq!("Hello…", opts(9).shorten("Hello, (big) world"));
+    // without spaces, as far as Unicode can tell each Japanese character is a word
+    assert_eq!("東京は晴…", opts(5).shorten("東京は晴れです"));
+  }
+
+  #[test]
+  fn excerpts_of_the_content_follow_the_summary() {
+    let story = Story {
+      headline: "Rust 2021 edition is out",
+      content: "The third edition of Rust brings disjoint captures in closures.",
+    };
+    let opts = SummaryOptions::new()
+      .with_max_graphemes(16)
+      .at_word_boundary()
+      .with_excerpt(30);
+    assert_eq!(
+      "Rust 2021…\nThe third edition of Rust…",
+      story.summarize_with(opts)
+    );
+  }
+}
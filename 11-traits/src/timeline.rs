@@ -0,0 +1,299 @@
+// A timeline puts the items of any number of sources together, newest first. Each source has
+// to be sorted that way already, which is what lets the timeline take the items one at a time
+// rather than reading every source to the end: the next item is always the newest of the ones
+// at the front of the sources.
+//
+// An item that's in more than one source, as in a story that's in two feeds, shows up once.
+// Items are the same when they're of the same type and have the same id, so that an article
+// and a tweet that happen to share an id are both shown.
+use crate::Timestamped;
+use chrono::{DateTime, Duration, Utc};
+use std::any::TypeId;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::iter::Peekable;
+
+type Source = Peekable<Box<dyn Iterator<Item = Box<dyn Timestamped>>>>;
+
+#[derive(Default)]
+pub struct Timeline {
+  sources: Vec<Source>,
+  // the types and ids of the items that have been taken so far
+  seen: HashSet<(TypeId, String)>,
+}
+
+impl Timeline {
+  pub fn new() -> Timeline {
+    Timeline::default()
+  }
+
+  pub fn with_source<I>(mut self, source: I) -> Timeline
+  where
+    I: IntoIterator<Item = Box<dyn Timestamped>>,
+    I::IntoIter: 'static,
+  {
+    let source: Box<dyn Iterator<Item = Box<dyn Timestamped>>> = Box::new(source.into_iter());
+    self.sources.push(source.peekable());
+    self
+  }
+
+  // The `limit` newest items, the best scoring first. Items that score the same stay newest
+  // first.
+  pub fn ranked(self, scorer: &dyn Score, limit: usize) -> Vec<Box<dyn Timestamped>> {
+    let mut scored: Vec<(f64, Box<dyn Timestamped>)> = self
+      .take(limit)
+      .map(|item| (scorer.score(item.as_ref()), item))
+      .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.into_iter().map(|(_, item)| item).collect()
+  }
+}
+
+impl Iterator for Timeline {
+  type Item = Box<dyn Timestamped>;
+
+  fn next(&mut self) -> Option<Box<dyn Timestamped>> {
+    loop {
+      // when items were posted at the same time, the one from the first source goes first
+      let (newest, _) = self
+        .sources
+        .iter_mut()
+        .enumerate()
+        .filter_map(|(i, source)| source.peek().map(|item| (i, item.posted_at())))
+        .max_by_key(|&(i, posted_at)| (posted_at, Reverse(i)))?;
+      let item = self.sources[newest].next().unwrap();
+      if self.seen.insert((item.as_ref().type_id(), item.id())) {
+        return Some(item);
+      }
+    }
+  }
+}
+
+// How good an item is for a timeline's ranking, the higher the better.
+pub trait Score {
+  fn score(&self, item: &dyn Timestamped) -> f64;
+}
+
+// Any function of an item scores, which is also how scores are put together, as in
+// `|item: &dyn Timestamped| recency.score(item) * originality.score(item)`.
+impl<F: Fn(&dyn Timestamped) -> f64> Score for F {
+  fn score(&self, item: &dyn Timestamped) -> f64 {
+    self(item)
+  }
+}
+
+// Newer is better: an item posted `now` scores 1, and the score halves every `half_life`.
+pub struct Recency {
+  pub now: DateTime<Utc>,
+  pub half_life: Duration,
+}
+
+impl Recency {
+  pub fn new(now: DateTime<Utc>, half_life: Duration) -> Recency {
+    Recency { now, half_life }
+  }
+}
+
+impl Score for Recency {
+  fn score(&self, item: &dyn Timestamped) -> f64 {
+    let age = (self.now - item.posted_at()).num_seconds().max(0) as f64;
+    let half_life = self.half_life.num_seconds().max(1) as f64;
+    0.5f64.powf(age / half_life)
+  }
+}
+
+// Original items are better than replies and retweets, which score what's given here where
+// the others score 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Originality {
+  pub reply: f64,
+  pub retweet: f64,
+}
+
+impl Default for Originality {
+  fn default() -> Originality {
+    Originality {
+      reply: 0.5,
+      retweet: 0.25,
+    }
+  }
+}
+
+impl Score for Originality {
+  fn score(&self, item: &dyn Timestamped) -> f64 {
+    if item.is_retweet() {
+      self.retweet
+    } else if item.is_reply() {
+      self.reply
+    } else {
+      1.0
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Summary;
+  use chrono::TimeZone;
+  use std::cell::Cell;
+  use std::rc::Rc;
+
+  struct Post {
+    id: &'static str,
+    hour: u32,
+    reply: bool,
+    retweet: bool,
+  }
+
+  impl Summary for Post {
+    fn authored_by(&self) -> String {
+      "@someone".to_string()
+    }
+
+    fn is_reply(&self) -> bool {
+      self.reply
+    }
+
+    fn is_retweet(&self) -> bool {
+      self.retweet
+    }
+  }
+
+  impl Timestamped for Post {
+    fn id(&self) -> String {
+      self.id.to_string()
+    }
+
+    fn posted_at(&self) -> DateTime<Utc> {
+      Utc.with_ymd_and_hms(2021, 10, 21, self.hour, 0, 0).unwrap()
+    }
+  }
+
+  fn post(id: &'static str, hour: u32) -> Box<dyn Timestamped> {
+    Box::new(Post {
+      id,
+      hour,
+      reply: false,
+      retweet: false,
+    })
+  }
+
+  fn ids(items: impl IntoIterator<Item = Box<dyn Timestamped>>) -> Vec<String> {
+    items.into_iter().map(|item| item.id()).collect()
+  }
+
+  #[test]
+  fn sources_are_merged_newest_first() {
+    let timeline = Timeline::new()
+      .with_source(vec![post("a", 9), post("b", 5), post("c", 1)])
+      .with_source(vec![post("d", 7), post("e", 5)])
+      .with_source(Vec::new());
+    assert_eq!(vec!["a", "d", "b", "e", "c"], ids(timeline));
+  }
+
+  #[test]
+  fn items_in_several_sources_show_up_once() {
+    let timeline = Timeline::new()
+      .with_source(vec![post("a", 9), post("shared", 5)])
+      .with_source(vec![post("shared", 5), post("b", 3), post("a", 1)]);
+    assert_eq!(vec!["a", "shared", "b"], ids(timeline));
+  }
+
+  #[test]
+  fn items_of_different_types_can_share_ids() {
+    struct Story;
+
+    impl Summary for Story {
+      fn authored_by(&self) -> String {
+        "Ana".to_string()
+      }
+    }
+
+    impl Timestamped for Story {
+      fn id(&self) -> String {
+        "1".to_string()
+      }
+
+      fn posted_at(&self) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2021, 10, 21, 5, 0, 0).unwrap()
+      }
+    }
+
+    let timeline = Timeline::new()
+      .with_source(vec![post("1", 9), post("1", 5)])
+      .with_source(vec![Box::new(Story) as Box<dyn Timestamped>]);
+    let items: Vec<(String, String)> = timeline
+      .map(|item| (item.id(), item.authored_by()))
+      .collect();
+    assert_eq!(
+      vec![
+        ("1".to_string(), "@someone".to_string()),
+        ("1".to_string(), "Ana".to_string()),
+      ],
+      items
+    );
+  }
+
+  #[test]
+  fn sources_are_read_as_far_as_needed() {
+    let taken = Rc::new(Cell::new(0));
+    let counted = |taken: &Rc<Cell<usize>>, hours: Vec<u32>| {
+      let taken = taken.clone();
+      hours.into_iter().map(move |hour| {
+        taken.set(taken.get() + 1);
+        post("x", hour)
+      })
+    };
+    let mut timeline = Timeline::new()
+      .with_source(counted(&taken, (0..24).rev().collect()))
+      .with_source(counted(&taken, vec![12]));
+    timeline.next();
+    // just the front of each source
+    assert_eq!(2, taken.get());
+  }
+
+  #[test]
+  fn ranking_is_up_to_the_scorer() {
+    let items = || {
+      vec![
+        Box::new(Post {
+          id: "retweet",
+          hour: 9,
+          reply: false,
+          retweet: true,
+        }) as Box<dyn Timestamped>,
+        Box::new(Post {
+          id: "reply",
+          hour: 8,
+          reply: true,
+          retweet: false,
+        }),
+        post("old", 2),
+        post("older", 1),
+      ]
+    };
+    let originality = Originality::default();
+    let ranked = Timeline::new()
+      .with_source(items())
+      .ranked(&originality, 10);
+    assert_eq!(vec!["old", "older", "reply", "retweet"], ids(ranked));
+
+    let recency = Recency::new(
+      Utc.with_ymd_and_hms(2021, 10, 21, 10, 0, 0).unwrap(),
+      Duration::hours(2),
+    );
+    let both = |item: &dyn Timestamped| recency.score(item) * originality.score(item);
+    let ranked = Timeline::new().with_source(items()).ranked(&both, 3);
+    assert_eq!(vec!["reply", "retweet", "old"], ids(ranked));
+  }
+
+  #[test]
+  fn recency_halves_with_age() {
+    let now = Utc.with_ymd_and_hms(2021, 10, 21, 10, 0, 0).unwrap();
+    let recency = Recency::new(now, Duration::hours(2));
+    assert_eq!(1.0, recency.score(post("now", 10).as_ref()));
+    assert_eq!(0.5, recency.score(post("earlier", 8).as_ref()));
+    assert_eq!(0.25, recency.score(post("earliest", 6).as_ref()));
+  }
+}
@@ -0,0 +1,191 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Role {
+  Author,
+  Reviewer,
+  Admin,
+}
+
+// Someone acting on a post; actors are told apart by their name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Actor {
+  pub name: String,
+  pub role: Role,
+}
+
+impl Actor {
+  pub fn new(name: &str, role: Role) -> Actor {
+    Actor {
+      name: name.to_string(),
+      role,
+    }
+  }
+
+  pub fn author(name: &str) -> Actor {
+    Actor::new(name, Role::Author)
+  }
+
+  pub fn reviewer(name: &str) -> Actor {
+    Actor::new(name, Role::Reviewer)
+  }
+
+  pub fn admin(name: &str) -> Actor {
+    Actor::new(name, Role::Admin)
+  }
+}
+
+// What a policy decides upon: the transitions, and editing the content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+  Edit,
+  RequestReview,
+  Approve,
+  Reject,
+  Schedule,
+  Unschedule,
+  Publish,
+  Revise,
+  Restore,
+}
+
+impl Action {
+  pub fn name(&self) -> &'static str {
+    match self {
+      Action::Edit => "edit",
+      Action::RequestReview => "request_review",
+      Action::Approve => "approve",
+      Action::Reject => "reject",
+      Action::Schedule => "schedule",
+      Action::Unschedule => "unschedule",
+      Action::Publish => "publish",
+      Action::Revise => "revise",
+      Action::Restore => "restore",
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PermissionError {
+  Forbidden { actor: String, action: Action },
+  SelfApproval { actor: String },
+}
+
+impl fmt::Display for PermissionError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      PermissionError::Forbidden { actor, action } => {
+        write!(f, "{} is not allowed to {}", actor, action.name())
+      }
+      PermissionError::SelfApproval { actor } => {
+        write!(f, "{} can't approve their own post", actor)
+      }
+    }
+  }
+}
+
+impl Error for PermissionError {}
+
+// Decides who may do what with a post written by `author`.
+pub trait Policy: Send + Sync {
+  fn permits(&self, actor: &Actor, action: Action, author: &str) -> bool;
+}
+
+// Authors write their own posts, take them to review and revise them, reviewers approve, reject
+// and (un)schedule posts, while admins may do anything.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RolePolicy;
+
+impl Policy for RolePolicy {
+  fn permits(&self, actor: &Actor, action: Action, author: &str) -> bool {
+    match actor.role {
+      Role::Admin => true,
+      Role::Author => {
+        actor.name == author
+          && matches!(
+            action,
+            Action::Edit | Action::RequestReview | Action::Revise | Action::Restore
+          )
+      }
+      Role::Reviewer => matches!(
+        action,
+        Action::Approve | Action::Reject | Action::Schedule | Action::Unschedule | Action::Publish
+      ),
+    }
+  }
+}
+
+// Runs an action past the policy. Whatever the policy says, nobody gets to approve their own post.
+pub fn authorize(
+  policy: &dyn Policy,
+  actor: &Actor,
+  action: Action,
+  author: &str,
+) -> Result<(), PermissionError> {
+  if action == Action::Approve && actor.name == author {
+    return Err(PermissionError::SelfApproval {
+      actor: actor.name.clone(),
+    });
+  }
+
+  if policy.permits(actor, action, author) {
+    Ok(())
+  } else {
+    Err(PermissionError::Forbidden {
+      actor: actor.name.clone(),
+      action,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  struct AnythingGoes;
+
+  impl Policy for AnythingGoes {
+    fn permits(&self, _actor: &Actor, _action: Action, _author: &str) -> bool {
+      true
+    }
+  }
+
+  #[test]
+  fn role_policy_separates_authors_and_reviewers() {
+    let alice = Actor::author("alice");
+    let bob = Actor::reviewer("bob");
+
+    assert!(authorize(&RolePolicy, &alice, Action::RequestReview, "alice").is_ok());
+    assert_eq!(
+      Err(PermissionError::Forbidden {
+        actor: "alice".to_string(),
+        action: Action::Reject,
+      }),
+      authorize(&RolePolicy, &alice, Action::Reject, "alice")
+    );
+    assert!(authorize(&RolePolicy, &bob, Action::Approve, "alice").is_ok());
+    assert!(authorize(&RolePolicy, &bob, Action::RequestReview, "alice").is_err());
+  }
+
+  #[test]
+  fn authors_act_only_on_their_own_posts() {
+    let carol = Actor::author("carol");
+    assert!(authorize(&RolePolicy, &carol, Action::Revise, "alice").is_err());
+    assert!(authorize(&RolePolicy, &carol, Action::Edit, "alice").is_err());
+    assert!(authorize(&RolePolicy, &carol, Action::Edit, "carol").is_ok());
+    assert!(authorize(&RolePolicy, &Actor::reviewer("bob"), Action::Edit, "carol").is_err());
+  }
+
+  #[test]
+  fn nobody_approves_their_own_post() {
+    let root = Actor::admin("root");
+    assert!(authorize(&RolePolicy, &root, Action::Reject, "root").is_ok());
+    assert_eq!(
+      Err(PermissionError::SelfApproval {
+        actor: "root".to_string()
+      }),
+      authorize(&AnythingGoes, &root, Action::Approve, "root")
+    );
+  }
+}
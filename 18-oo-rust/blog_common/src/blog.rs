@@ -0,0 +1,241 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::Hash;
+
+// What the Blog needs to know about the posts it keeps, whichever way they implement their states.
+pub trait BlogPost {
+  type Status: Copy + Eq + Hash + fmt::Debug;
+
+  fn status(&self) -> Self::Status;
+
+  // The content readers get to see: empty for anything that isn't published (yet).
+  fn published_content(&self) -> &str;
+}
+
+// Posts keep their id for as long as they are in the blog, and ids of removed posts aren't reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PostId(u64);
+
+impl fmt::Display for PostId {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "#{}", self.0)
+  }
+}
+
+pub struct Blog<P> {
+  posts: BTreeMap<PostId, P>,
+  next_id: u64,
+}
+
+impl<P> Default for Blog<P> {
+  fn default() -> Self {
+    Self {
+      posts: BTreeMap::new(),
+      next_id: 1,
+    }
+  }
+}
+
+impl<P: BlogPost> Blog<P> {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn insert(&mut self, post: P) -> PostId {
+    let id = PostId(self.next_id);
+    self.next_id += 1;
+    self.posts.insert(id, post);
+    id
+  }
+
+  pub fn get(&self, id: PostId) -> Option<&P> {
+    self.posts.get(&id)
+  }
+
+  pub fn get_mut(&mut self, id: PostId) -> Option<&mut P> {
+    self.posts.get_mut(&id)
+  }
+
+  pub fn remove(&mut self, id: PostId) -> Option<P> {
+    self.posts.remove(&id)
+  }
+
+  pub fn len(&self) -> usize {
+    self.posts.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.posts.is_empty()
+  }
+
+  // All posts, in the order they were inserted.
+  pub fn iter(&self) -> impl Iterator<Item = (PostId, &P)> {
+    self.posts.iter().map(|(id, post)| (*id, post))
+  }
+
+  pub fn with_status(&self, status: P::Status) -> Vec<PostId> {
+    self
+      .iter()
+      .filter(|(_, post)| post.status() == status)
+      .map(|(id, _)| id)
+      .collect()
+  }
+
+  pub fn by_status(&self) -> HashMap<P::Status, Vec<PostId>> {
+    let mut listing: HashMap<P::Status, Vec<PostId>> = HashMap::new();
+    for (id, post) in self.iter() {
+      listing.entry(post.status()).or_default().push(id);
+    }
+    listing
+  }
+
+  // Case-insensitive search for posts whose published content contains all the words of the
+  // query. The best matches (most occurrences of the query's words) come first.
+  pub fn search(&self, query: &str) -> Vec<PostId> {
+    let terms = words(query);
+    if terms.is_empty() {
+      return Vec::new();
+    }
+
+    let mut hits: Vec<(usize, PostId)> = self
+      .iter()
+      .filter_map(|(id, post)| {
+        let content = words(post.published_content());
+        let mut score = 0;
+        for term in terms.iter() {
+          match content.iter().filter(|word| *word == term).count() {
+            0 => return None,
+            n => score += n,
+          }
+        }
+        Some((score, id))
+      })
+      .collect();
+
+    hits.sort_by(|(a_score, a_id), (b_score, b_id)| b_score.cmp(a_score).then(a_id.cmp(b_id)));
+    hits.into_iter().map(|(_, id)| id).collect()
+  }
+
+  // Applies the same transition to every post matching `filter`, reporting the outcome per post.
+  pub fn update_where<E>(
+    &mut self,
+    filter: impl Fn(&P) -> bool,
+    mut transition: impl FnMut(&mut P) -> Result<(), E>,
+  ) -> Vec<(PostId, Result<(), E>)> {
+    self
+      .posts
+      .iter_mut()
+      .filter(|(_, post)| filter(post))
+      .map(|(id, post)| (*id, transition(post)))
+      .collect()
+  }
+
+  pub fn update_with_status<E>(
+    &mut self,
+    status: P::Status,
+    transition: impl FnMut(&mut P) -> Result<(), E>,
+  ) -> Vec<(PostId, Result<(), E>)> {
+    self.update_where(|post| post.status() == status, transition)
+  }
+}
+
+fn words(text: &str) -> Vec<String> {
+  text
+    .split(|c: char| !c.is_alphanumeric())
+    .filter(|word| !word.is_empty())
+    .map(|word| word.to_lowercase())
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  struct Note {
+    published: bool,
+    content: String,
+  }
+
+  impl Note {
+    fn new(published: bool, content: &str) -> Note {
+      Note {
+        published,
+        content: content.to_string(),
+      }
+    }
+  }
+
+  impl BlogPost for Note {
+    type Status = bool;
+
+    fn status(&self) -> bool {
+      self.published
+    }
+
+    fn published_content(&self) -> &str {
+      if self.published {
+        &self.content
+      } else {
+        ""
+      }
+    }
+  }
+
+  #[test]
+  fn ids_are_stable_and_never_reused() {
+    let mut blog = Blog::new();
+    let first = blog.insert(Note::new(false, "one"));
+    let second = blog.insert(Note::new(false, "two"));
+    blog.remove(first);
+    let third = blog.insert(Note::new(false, "three"));
+
+    assert_ne!(first, third);
+    assert_eq!("two", blog.get(second).unwrap().content);
+    assert!(blog.get(first).is_none());
+    assert_eq!(2, blog.len());
+  }
+
+  #[test]
+  fn posts_are_listed_by_status() {
+    let mut blog = Blog::new();
+    let a = blog.insert(Note::new(true, "a"));
+    let b = blog.insert(Note::new(false, "b"));
+    let c = blog.insert(Note::new(true, "c"));
+
+    assert_eq!(vec![a, c], blog.with_status(true));
+    let listing = blog.by_status();
+    assert_eq!(vec![b], listing[&false]);
+  }
+
+  #[test]
+  fn search_looks_at_published_content_only() {
+    let mut blog = Blog::new();
+    let salad = blog.insert(Note::new(true, "I ate a salad. Salad is great!"));
+    let lunch = blog.insert(Note::new(true, "I ate a salad for lunch today"));
+    blog.insert(Note::new(false, "A secret salad recipe"));
+
+    assert_eq!(vec![salad, lunch], blog.search("SALAD"));
+    assert_eq!(vec![lunch], blog.search("salad lunch"));
+    assert!(blog.search("secret").is_empty());
+    assert!(blog.search("  ").is_empty());
+  }
+
+  #[test]
+  fn bulk_updates_report_each_outcome() {
+    let mut blog = Blog::new();
+    let a = blog.insert(Note::new(false, "a"));
+    let b = blog.insert(Note::new(false, "b!"));
+    blog.insert(Note::new(true, "c"));
+
+    let outcomes = blog.update_with_status(false, |note| {
+      if note.content.ends_with('!') {
+        return Err("too loud");
+      }
+      note.published = true;
+      Ok(())
+    });
+
+    assert_eq!(vec![(a, Ok(())), (b, Err("too loud"))], outcomes);
+    assert_eq!(vec![b], blog.with_status(false));
+  }
+}
@@ -0,0 +1,68 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Seconds since the UNIX epoch.
+pub type Timestamp = u64;
+
+// Where posts get the current time from. Posts hold their clock behind an `Arc`, so that tests
+// can keep a handle on a ManualClock and move the time forward deterministically.
+pub trait Clock: Send + Sync {
+  fn now(&self) -> Timestamp;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+  fn now(&self) -> Timestamp {
+    SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|d| d.as_secs())
+      .unwrap_or(0)
+  }
+}
+
+// A clock that only moves when told to.
+#[derive(Debug, Default)]
+pub struct ManualClock {
+  now: AtomicU64,
+}
+
+impl ManualClock {
+  pub fn new(now: Timestamp) -> Self {
+    Self {
+      now: AtomicU64::new(now),
+    }
+  }
+
+  pub fn set(&self, now: Timestamp) {
+    self.now.store(now, Ordering::SeqCst);
+  }
+
+  pub fn advance(&self, seconds: u64) {
+    self.now.fetch_add(seconds, Ordering::SeqCst);
+  }
+}
+
+impl Clock for ManualClock {
+  fn now(&self) -> Timestamp {
+    self.now.load(Ordering::SeqCst)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn manual_clock_moves_only_when_told() {
+    let clock = ManualClock::new(100);
+    assert_eq!(100, clock.now());
+
+    clock.advance(20);
+    assert_eq!(120, clock.now());
+
+    clock.set(5);
+    assert_eq!(5, clock.now());
+  }
+}
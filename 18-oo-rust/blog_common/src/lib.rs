@@ -0,0 +1,10 @@
+// --------------------------------------------------------------------------------------------------------
+// Building blocks shared by both blog implementations (blog_nonrust_way and blog_rust_way). These
+// don't know anything about the states a post can be in, they only deal with the post's content.
+// --------------------------------------------------------------------------------------------------------
+pub mod access;
+pub mod blog;
+pub mod clock;
+pub mod markdown;
+pub mod revisions;
+pub mod storage;
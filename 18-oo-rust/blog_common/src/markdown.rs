@@ -0,0 +1,865 @@
+// Renders post content written in a subset of CommonMark to HTML: ATX headings, paragraphs,
+// emphasis, code spans, links, bullet and ordered lists, and fenced or indented code blocks.
+// Anything else (raw HTML included) comes out as escaped text, so the output is safe to embed.
+//
+// Which content may be rendered at all is up to the posts: only published content is.
+use std::collections::HashMap;
+
+enum Block {
+  Heading(usize, String),
+  Paragraph(String),
+  Code {
+    info: String,
+    text: String,
+  },
+  List {
+    // the number of the first item of an ordered list, None for a bullet list
+    start: Option<u64>,
+    tight: bool,
+    items: Vec<Vec<Block>>,
+  },
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Marker {
+  Bullet(char),
+  Ordered(char),
+}
+
+// A list item marker at the start of a line.
+struct ListItem {
+  marker: Marker,
+  number: u64,
+  // where the item's content starts; continuation lines have to be indented that far
+  content_offset: usize,
+}
+
+pub fn render(markdown: &str) -> String {
+  let lines: Vec<String> = markdown.lines().map(expand_tabs).collect();
+  let mut html = String::new();
+  for block in parse_blocks(&lines) {
+    render_block(&block, false, &mut html);
+  }
+  html
+}
+
+pub fn escape(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    push_escaped(&mut escaped, c);
+  }
+  escaped
+}
+
+fn push_escaped(out: &mut String, c: char) {
+  match c {
+    '&' => out.push_str("&amp;"),
+    '<' => out.push_str("&lt;"),
+    '>' => out.push_str("&gt;"),
+    '"' => out.push_str("&quot;"),
+    c => out.push(c),
+  }
+}
+
+// Tabs only matter for indentation, where they count as 4 spaces.
+fn expand_tabs(line: &str) -> String {
+  let mut expanded = String::new();
+  let mut chars = line.chars();
+  for c in chars.by_ref() {
+    match c {
+      '\t' => expanded.push_str(&" ".repeat(4 - expanded.len() % 4)),
+      ' ' => expanded.push(' '),
+      c => {
+        expanded.push(c);
+        break;
+      }
+    }
+  }
+  expanded.extend(chars);
+  expanded
+}
+
+fn indent(line: &str) -> usize {
+  line.len() - line.trim_start_matches(' ').len()
+}
+
+fn is_blank(line: &str) -> bool {
+  line.trim().is_empty()
+}
+
+// ---------------------------------------------------------------------------------------------
+// Blocks
+// ---------------------------------------------------------------------------------------------
+
+fn parse_blocks(lines: &[String]) -> Vec<Block> {
+  let mut blocks = Vec::new();
+  let mut i = 0;
+  while i < lines.len() {
+    let line = &lines[i];
+    if is_blank(line) {
+      i += 1;
+    } else if let Some((fence, len)) = opening_fence(line) {
+      i = parse_fenced_code(lines, i, fence, len, &mut blocks);
+    } else if indent(line) >= 4 {
+      i = parse_indented_code(lines, i, &mut blocks);
+    } else if let Some((level, text)) = heading(line) {
+      blocks.push(Block::Heading(level, text));
+      i += 1;
+    } else if let Some(item) = list_item(line) {
+      i = parse_list(lines, i, item, &mut blocks);
+    } else {
+      let start = i;
+      while i < lines.len() && !is_blank(&lines[i]) && (i == start || !interrupts(&lines[i])) {
+        i += 1;
+      }
+      let text: Vec<&str> = lines[start..i].iter().map(|line| line.trim()).collect();
+      blocks.push(Block::Paragraph(text.join("\n")));
+    }
+  }
+  blocks
+}
+
+// Whether the line starts a new block rather than continuing a paragraph.
+fn interrupts(line: &str) -> bool {
+  indent(line) < 4
+    && (opening_fence(line).is_some() || heading(line).is_some() || list_item(line).is_some())
+}
+
+fn opening_fence(line: &str) -> Option<(char, usize)> {
+  if indent(line) >= 4 {
+    return None;
+  }
+  let trimmed = line.trim_start();
+  let fence = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+  let len = trimmed.chars().take_while(|c| *c == fence).count();
+  let info = &trimmed[len..];
+  if len < 3 || (fence == '`' && info.contains('`')) {
+    return None;
+  }
+  Some((fence, len))
+}
+
+fn parse_fenced_code(
+  lines: &[String],
+  start: usize,
+  fence: char,
+  len: usize,
+  blocks: &mut Vec<Block>,
+) -> usize {
+  let opening = &lines[start];
+  let fence_indent = indent(opening);
+  let info = opening.trim_start()[len..].trim();
+  // only the first word of the info string names the language
+  let info = info.split_whitespace().next().unwrap_or("").to_string();
+
+  let mut text = String::new();
+  let mut i = start + 1;
+  while i < lines.len() {
+    let line = &lines[i];
+    let trimmed = line.trim_start();
+    let closing_len = trimmed.chars().take_while(|c| *c == fence).count();
+    if indent(line) < 4 && closing_len >= len && trimmed[closing_len..].trim().is_empty() {
+      i += 1;
+      break;
+    }
+    text.push_str(&line[indent(line).min(fence_indent)..]);
+    text.push('\n');
+    i += 1;
+  }
+
+  blocks.push(Block::Code { info, text });
+  i
+}
+
+fn parse_indented_code(lines: &[String], start: usize, blocks: &mut Vec<Block>) -> usize {
+  let mut i = start;
+  while i < lines.len() && (is_blank(&lines[i]) || indent(&lines[i]) >= 4) {
+    i += 1;
+  }
+  // trailing blank lines belong to whatever comes next
+  while is_blank(&lines[i - 1]) {
+    i -= 1;
+  }
+
+  let mut text = String::new();
+  for line in &lines[start..i] {
+    text.push_str(line.get(4..).unwrap_or(""));
+    text.push('\n');
+  }
+  blocks.push(Block::Code {
+    info: String::new(),
+    text,
+  });
+  i
+}
+
+fn heading(line: &str) -> Option<(usize, String)> {
+  if indent(line) >= 4 {
+    return None;
+  }
+  let trimmed = line.trim();
+  let level = trimmed.chars().take_while(|c| *c == '#').count();
+  let rest = &trimmed[level..];
+  if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with(' ')) {
+    return None;
+  }
+
+  // an optional closing sequence of #s is dropped, if it's separated by a space
+  let mut text = rest.trim();
+  let without_closing = text.trim_end_matches('#');
+  if without_closing.is_empty() || without_closing.ends_with(' ') {
+    text = without_closing.trim_end();
+  }
+  Some((level, text.to_string()))
+}
+
+fn list_item(line: &str) -> Option<ListItem> {
+  let marker_indent = indent(line);
+  if marker_indent >= 4 {
+    return None;
+  }
+  let trimmed = &line[marker_indent..];
+
+  let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+  let (marker, number, marker_len) = match trimmed[digits..].chars().next() {
+    Some(c @ ('-' | '*' | '+')) if digits == 0 => (Marker::Bullet(c), 1, 1),
+    Some(c @ ('.' | ')')) if (1..=9).contains(&digits) => (
+      Marker::Ordered(c),
+      trimmed[..digits].parse().ok()?,
+      digits + 1,
+    ),
+    _ => return None,
+  };
+
+  let rest = &trimmed[marker_len..];
+  if !rest.is_empty() && !rest.starts_with(' ') {
+    return None;
+  }
+  // an item's content is indented by up to 4 spaces after the marker; more than that makes
+  // it an indented code block, which starts a single space after the marker
+  let spaces = indent(rest);
+  let spaces = if spaces == 0 || spaces > 4 || is_blank(rest) {
+    1
+  } else {
+    spaces
+  };
+
+  Some(ListItem {
+    marker,
+    number,
+    content_offset: marker_indent + marker_len + spaces,
+  })
+}
+
+fn parse_list(lines: &[String], start: usize, first: ListItem, blocks: &mut Vec<Block>) -> usize {
+  let marker = first.marker;
+  let mut items = Vec::new();
+  let mut tight = true;
+  let mut i = start;
+  let mut item = Some(first);
+
+  while let Some(ListItem { content_offset, .. }) = item {
+    let mut content = vec![lines[i].get(content_offset..).unwrap_or("").to_string()];
+    i += 1;
+    while i < lines.len() {
+      let line = &lines[i];
+      if is_blank(line) {
+        content.push(String::new());
+      } else if indent(line) >= content_offset {
+        content.push(line[content_offset..].to_string());
+      } else if !is_blank(content.last().unwrap()) && !interrupts(line) && indent(line) < 4 {
+        // a "lazy" continuation of the item's last paragraph
+        content.push(line.trim().to_string());
+      } else {
+        break;
+      }
+      i += 1;
+    }
+
+    // blank lines at the end of an item separate it from the next one
+    let mut trailing_blanks = 0;
+    while content.len() > 1 && is_blank(content.last().unwrap()) {
+      content.pop();
+      trailing_blanks += 1;
+    }
+    let item_blocks = parse_blocks(&content);
+    if item_blocks.len() > 1 && content.iter().any(|line| is_blank(line)) {
+      tight = false;
+    }
+    items.push(item_blocks);
+
+    item = lines
+      .get(i)
+      .and_then(|line| list_item(line))
+      .filter(|next| next.marker == marker);
+    if item.is_some() && trailing_blanks > 0 {
+      tight = false;
+    }
+    if item.is_none() {
+      // the blank lines weren't followed by another item after all
+      i -= trailing_blanks;
+    }
+  }
+
+  let start = match marker {
+    Marker::Bullet(_) => None,
+    Marker::Ordered(_) => list_item(&lines[start]).map(|item| item.number),
+  };
+  blocks.push(Block::List {
+    start,
+    tight,
+    items,
+  });
+  i
+}
+
+fn render_block(block: &Block, tight: bool, html: &mut String) {
+  match block {
+    Block::Heading(level, text) => {
+      html.push_str(&format!("<h{}>{}</h{}>\n", level, inline(text), level));
+    }
+    // the paragraphs of tight list items aren't wrapped in <p> tags
+    Block::Paragraph(text) if tight => html.push_str(&inline(text)),
+    Block::Paragraph(text) => html.push_str(&format!("<p>{}</p>\n", inline(text))),
+    Block::Code { info, text } => {
+      html.push_str("<pre><code");
+      if !info.is_empty() {
+        html.push_str(&format!(" class=\"language-{}\"", escape(info)));
+      }
+      html.push('>');
+      html.push_str(&escape(text));
+      html.push_str("</code></pre>\n");
+    }
+    Block::List {
+      start,
+      tight,
+      items,
+    } => {
+      let tag = match start {
+        None => "ul",
+        Some(_) => "ol",
+      };
+      match start {
+        Some(n) if *n != 1 => html.push_str(&format!("<ol start=\"{}\">\n", n)),
+        _ => html.push_str(&format!("<{}>\n", tag)),
+      }
+      for item in items {
+        html.push_str("<li>");
+        for block in item {
+          let bare = *tight && matches!(block, Block::Paragraph(_));
+          if !bare && !html.ends_with('\n') {
+            html.push('\n');
+          }
+          render_block(block, *tight, html);
+        }
+        html.push_str("</li>\n");
+      }
+      html.push_str(&format!("</{}>\n", tag));
+    }
+  }
+}
+
+// ---------------------------------------------------------------------------------------------
+// Inlines
+// ---------------------------------------------------------------------------------------------
+
+fn inline(text: &str) -> String {
+  let chars: Vec<char> = text.chars().collect();
+  let mut html = String::new();
+  render_inline(&chars, 0, &mut html);
+  html
+}
+
+// How deep emphasis and links go inside each other before the openers of any more of them are
+// taken as text. Each level renders the text inside it over again, so without a limit deeply
+// nested input would take quadratic time, and run out of stack.
+const MAX_NESTING: usize = 32;
+
+// `depth` is how many emphases and links the text is inside of.
+fn render_inline(chars: &[char], depth: usize, html: &mut String) {
+  let mut ahead = Lookahead::new(chars, depth);
+  let mut i = 0;
+  while i < chars.len() {
+    let c = chars[i];
+    let consumed = match c {
+      '\\' => match chars.get(i + 1) {
+        Some(next) if next.is_ascii_punctuation() => {
+          push_escaped(html, *next);
+          Some(2)
+        }
+        _ => None,
+      },
+      '`' => code_span(chars, i, &mut ahead, html),
+      '*' | '_' if depth < MAX_NESTING => emphasis(chars, i, &mut ahead, html),
+      '[' if depth < MAX_NESTING => link(chars, i, &mut ahead, html),
+      '<' => autolink(chars, i, &mut ahead, html),
+      _ => None,
+    };
+
+    match consumed {
+      Some(n) => i += n,
+      None => {
+        // a run of backticks that doesn't open a code span is taken literally as a whole
+        let run = if c == '`' { run_length(chars, i) } else { 1 };
+        for _ in 0..run {
+          push_escaped(html, c);
+        }
+        i += run;
+      }
+    }
+  }
+}
+
+fn run_length(chars: &[char], start: usize) -> usize {
+  chars[start..]
+    .iter()
+    .take_while(|c| **c == chars[start])
+    .count()
+}
+
+// What the inline constructs look for further on in the text. Looking from every opener to the
+// end of the text each time would take quadratic time on text that's full of openers without
+// closers, like `*a *a *a ...`, so where a bracket or a link destination ends is worked out in
+// one pass up front, and what's been looked for already is remembered.
+struct Lookahead {
+  // as for render_inline
+  depth: usize,
+  // the `]` that goes with the `[` at each index
+  brackets: Vec<Option<usize>>,
+  // where a link destination that starts at each index ends
+  destinations: Vec<usize>,
+  // for each kind of closer, as in `('*', 2)` for strong emphasis or `('`', 3)` for a code span
+  // opened by three backticks, the index from which on there's none of it
+  unclosed: HashMap<(char, usize), usize>,
+  // for each set of chars looked for, the last index it was looked for from and what was found
+  found: HashMap<&'static str, (usize, Option<usize>)>,
+}
+
+impl Lookahead {
+  fn new(chars: &[char], depth: usize) -> Lookahead {
+    // a backslash hides the char after it, whatever that is, from everything that's looked for
+    let mut escaped = vec![false; chars.len() + 1];
+    let mut brackets = vec![None; chars.len()];
+    let mut parens = vec![None; chars.len()];
+    let (mut open_brackets, mut open_parens) = (Vec::new(), Vec::new());
+    let mut i = 0;
+    while i < chars.len() {
+      match chars[i] {
+        '\\' => {
+          escaped[i + 1] = true;
+          i += 1;
+        }
+        '[' => open_brackets.push(i),
+        ']' => {
+          if let Some(open) = open_brackets.pop() {
+            brackets[open] = Some(i);
+          }
+        }
+        '(' => open_parens.push(i),
+        ')' => {
+          if let Some(open) = open_parens.pop() {
+            parens[open] = Some(i);
+          }
+        }
+        _ => {}
+      }
+      i += 1;
+    }
+
+    // a destination ends at whitespace or at a `)` that doesn't close a `(` in it
+    let mut next_space = vec![chars.len(); chars.len() + 1];
+    let mut destinations = vec![chars.len(); chars.len() + 1];
+    for i in (0..chars.len()).rev() {
+      next_space[i] = if chars[i].is_whitespace() && !escaped[i] {
+        i
+      } else {
+        next_space[i + 1]
+      };
+      destinations[i] = match chars[i] {
+        '\\' => destinations[(i + 2).min(chars.len())],
+        c if c.is_whitespace() || c == ')' => i,
+        '(' => match parens[i] {
+          Some(close) if close < next_space[i] => destinations[close + 1],
+          _ => next_space[i],
+        },
+        _ => destinations[i + 1],
+      };
+    }
+
+    Lookahead {
+      depth,
+      brackets,
+      destinations,
+      unclosed: HashMap::new(),
+      found: HashMap::new(),
+    }
+  }
+
+  // The first index from `from` on with one of `targets` there. The constructs are tried from
+  // left to right, so what was found last time is usually still the answer.
+  fn find(&mut self, chars: &[char], from: usize, targets: &'static str) -> Option<usize> {
+    if let Some(&(last, found)) = self.found.get(targets) {
+      if last <= from && found.is_none_or(|found| from <= found) {
+        return found;
+      }
+    }
+    let found = chars
+      .get(from..)
+      .and_then(|rest| rest.iter().position(|c| targets.contains(*c)))
+      .map(|i| from + i);
+    self.found.insert(targets, (from, found));
+    found
+  }
+
+  // Looking for a closer from `from` on finds nothing if looking from before it found nothing
+  // either: once past where they started, both looks go over the same runs of chars.
+  fn is_unclosed(&self, closer: (char, usize), from: usize) -> bool {
+    self
+      .unclosed
+      .get(&closer)
+      .is_some_and(|&unclosed| unclosed <= from)
+  }
+
+  fn set_unclosed(&mut self, closer: (char, usize), from: usize) {
+    let unclosed = self.unclosed.entry(closer).or_insert(from);
+    *unclosed = from.min(*unclosed);
+  }
+}
+
+// Each of these renders the construct starting at `start` and returns how many chars it took,
+// or None when there's no such construct there after all.
+
+fn code_span(
+  chars: &[char],
+  start: usize,
+  ahead: &mut Lookahead,
+  html: &mut String,
+) -> Option<usize> {
+  let len = run_length(chars, start);
+  if ahead.is_unclosed(('`', len), start + len) {
+    return None;
+  }
+  let mut i = start + len;
+  while i < chars.len() {
+    if chars[i] != '`' {
+      i += 1;
+      continue;
+    }
+    let closing = run_length(chars, i);
+    if closing != len {
+      i += closing;
+      continue;
+    }
+
+    let code: String = chars[start + len..i]
+      .iter()
+      .map(|c| if *c == '\n' { ' ' } else { *c })
+      .collect();
+    // a single space on both sides is stripped, so that code can start or end with a backtick
+    let code = match code.strip_prefix(' ').and_then(|c| c.strip_suffix(' ')) {
+      Some(stripped) if !code.trim().is_empty() => stripped,
+      _ => &code,
+    };
+    html.push_str(&format!("<code>{}</code>", escape(code)));
+    return Some(i + len - start);
+  }
+  ahead.set_unclosed(('`', len), start + len);
+  None
+}
+
+fn emphasis(
+  chars: &[char],
+  start: usize,
+  ahead: &mut Lookahead,
+  html: &mut String,
+) -> Option<usize> {
+  let delimiter = chars[start];
+  // an underscore can't start emphasis in the middle of a word
+  if delimiter == '_' && start > 0 && chars[start - 1].is_alphanumeric() {
+    return None;
+  }
+
+  let run = run_length(chars, start);
+  let (width, tag) = if run >= 2 { (2, "strong") } else { (1, "em") };
+  let opens = chars.get(start + width).is_some_and(|c| !c.is_whitespace());
+  if !opens {
+    return None;
+  }
+
+  match closing_delimiter(chars, start + width, delimiter, width, ahead) {
+    Some(end) => {
+      html.push_str(&format!("<{}>", tag));
+      render_inline(&chars[start + width..end], ahead.depth + 1, html);
+      html.push_str(&format!("</{}>", tag));
+      Some(end + width - start)
+    }
+    // `**` without a closing `**` may still be a `*` followed by emphasis
+    None if width == 2 => {
+      push_escaped(html, delimiter);
+      emphasis(chars, start + 1, ahead, html).map(|n| n + 1)
+    }
+    None => None,
+  }
+}
+
+// Finds the closing run of `width` delimiters, skipping over runs of other widths (which belong to
+// nested emphasis) and escaped characters.
+fn closing_delimiter(
+  chars: &[char],
+  from: usize,
+  delimiter: char,
+  width: usize,
+  ahead: &mut Lookahead,
+) -> Option<usize> {
+  if ahead.is_unclosed((delimiter, width), from) {
+    return None;
+  }
+  let mut i = from;
+  while i < chars.len() {
+    if chars[i] == '\\' {
+      i += 2;
+      continue;
+    }
+    if chars[i] != delimiter {
+      i += 1;
+      continue;
+    }
+
+    let run = run_length(chars, i);
+    let after_word = i > from && !chars[i - 1].is_whitespace();
+    let before_word = chars.get(i + run).is_some_and(|c| c.is_alphanumeric());
+    let closes = after_word && !(delimiter == '_' && before_word);
+    if closes && (run == width || (width == 2 && run > 2)) {
+      // in `***`, the last two close the strong emphasis and the first one a nested one
+      return Some(i + run - width);
+    }
+    i += run;
+  }
+  ahead.set_unclosed((delimiter, width), from);
+  None
+}
+
+fn link(chars: &[char], start: usize, ahead: &mut Lookahead, html: &mut String) -> Option<usize> {
+  // the link text may contain balanced brackets
+  let text_end = ahead.brackets[start]?;
+  if chars.get(text_end + 1) != Some(&'(') {
+    return None;
+  }
+
+  let mut i = skip_spaces(chars, text_end + 2);
+  let destination = if chars.get(i) == Some(&'<') {
+    let end = ahead.find(chars, i, ">\n")?;
+    if chars[end] != '>' {
+      return None;
+    }
+    let destination = &chars[i + 1..end];
+    i = end + 1;
+    destination
+  } else {
+    let begin = i;
+    i = ahead.destinations[i];
+    &chars[begin..i]
+  };
+
+  let mut j = skip_spaces(chars, i);
+  let mut title = None;
+  if j > i {
+    if let Some(quote @ ('"' | '\'')) = chars.get(j) {
+      let quotes = if *quote == '"' { "\"" } else { "'" };
+      let end = ahead.find(chars, j + 1, quotes)?;
+      title = Some(&chars[j + 1..end]);
+      j = skip_spaces(chars, end + 1);
+    }
+  }
+  if chars.get(j) != Some(&')') {
+    return None;
+  }
+
+  let href = unescape(destination);
+  if is_safe_url(&href) {
+    html.push_str(&format!("<a href=\"{}\"", escape(&href)));
+    if let Some(title) = title {
+      html.push_str(&format!(" title=\"{}\"", escape(&unescape(title))));
+    }
+    html.push('>');
+    render_inline(&chars[start + 1..text_end], ahead.depth + 1, html);
+    html.push_str("</a>");
+  } else {
+    // links that would run a script are reduced to their text
+    render_inline(&chars[start + 1..text_end], ahead.depth + 1, html);
+  }
+  Some(j + 1 - start)
+}
+
+fn autolink(
+  chars: &[char],
+  start: usize,
+  ahead: &mut Lookahead,
+  html: &mut String,
+) -> Option<usize> {
+  let end = ahead.find(chars, start, ">")?;
+  let url: String = chars[start + 1..end].iter().collect();
+  let scheme = url.split(':').next().unwrap_or("");
+  let is_url = url.contains(':')
+    && scheme.len() >= 2
+    && scheme
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || "+.-".contains(c))
+    && !url.chars().any(|c| c.is_whitespace() || c == '<');
+  if !is_url || !is_safe_url(&url) {
+    return None;
+  }
+
+  html.push_str(&format!(
+    "<a href=\"{}\">{}</a>",
+    escape(&url),
+    escape(&url)
+  ));
+  Some(end + 1 - start)
+}
+
+fn skip_spaces(chars: &[char], mut i: usize) -> usize {
+  while chars.get(i).is_some_and(|c| c.is_whitespace()) {
+    i += 1;
+  }
+  i
+}
+
+fn unescape(chars: &[char]) -> String {
+  let mut text = String::new();
+  let mut i = 0;
+  while i < chars.len() {
+    match chars.get(i + 1) {
+      Some(next) if chars[i] == '\\' && next.is_ascii_punctuation() => {
+        text.push(*next);
+        i += 2;
+      }
+      _ => {
+        text.push(chars[i]);
+        i += 1;
+      }
+    }
+  }
+  text
+}
+
+// Only web and mail links, and relative ones without a scheme, are let through. The URL is
+// looked at the way a browser would see it: browsers drop tabs and line breaks anywhere in a
+// URL, and control characters and spaces around it, so `java\tscript:` is still `javascript:`.
+fn is_safe_url(url: &str) -> bool {
+  let url: String = url
+    .trim_matches(|c: char| c <= '\x20')
+    .chars()
+    .filter(|c| !matches!(c, '\t' | '\n' | '\r'))
+    .collect::<String>()
+    .to_ascii_lowercase();
+  // a colon after the first `/`, `?` or `#` is part of the path, not a scheme
+  let scheme_end = url.find([':', '/', '?', '#']);
+  match scheme_end {
+    Some(end) if url[end..].starts_with(':') => ["http", "https", "mailto"].contains(&&url[..end]),
+    _ => true,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::fs;
+  use std::path::Path;
+
+  // Every `<name>.md` in golden/markdown has to render to exactly its `<name>.html`.
+  #[test]
+  fn renders_golden_files() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("golden/markdown");
+    let mut checked = 0;
+    for entry in fs::read_dir(&dir).unwrap() {
+      let path = entry.unwrap().path();
+      if path.extension().is_some_and(|ext| ext == "md") {
+        let markdown = fs::read_to_string(&path).unwrap();
+        let expected = fs::read_to_string(path.with_extension("html")).unwrap();
+        assert_eq!(expected, render(&markdown), "rendering {}", path.display());
+        checked += 1;
+      }
+    }
+    assert!(checked > 0, "no golden files in {}", dir.display());
+  }
+
+  #[test]
+  fn raw_html_is_escaped() {
+    assert_eq!(
+      "<p>&lt;script&gt;alert(&quot;hi&quot;)&lt;/script&gt; &amp; more</p>\n",
+      render("<script>alert(\"hi\")</script> & more")
+    );
+  }
+
+  #[test]
+  fn script_links_lose_their_href() {
+    assert_eq!(
+      "<p>click me</p>\n",
+      render("[click me](javascript:alert(1))")
+    );
+    // browsers ignore tabs in URLs and control characters in front of them
+    assert_eq!("<p>x</p>\n", render("[x](<java\tscript:alert(1)>)"));
+    assert_eq!("<p>x</p>\n", render("[x](\x01javascript:alert(1))"));
+    assert_eq!("<p>x</p>\n", render("[x]( \x0bJavaScript:alert(1))"));
+  }
+
+  // Openers without closers, or constructs nested very deep, used to take quadratic time: these
+  // took minutes before, and take milliseconds now.
+  #[test]
+  fn pathological_input_renders_in_linear_time() {
+    let started = std::time::Instant::now();
+    for (text, times) in [
+      ("*a ", 60_000),
+      ("_a ", 60_000),
+      ("**a ", 40_000),
+      ("[x](", 20_000),
+      ("[x](<", 20_000),
+      ("[x](a \"", 20_000),
+      ("[", 60_000),
+      ("<", 60_000),
+      ("`` `", 30_000),
+    ] {
+      let html = render(&text.repeat(times));
+      assert!(html.starts_with("<p>"), "{:?}", text);
+    }
+    let nested = format!("{}x{}", "[".repeat(20_000), "](a)".repeat(20_000));
+    assert!(render(&nested).starts_with("<p><a href=\"a\">"));
+    assert!(
+      started.elapsed().as_secs() < 10,
+      "took {:?}",
+      started.elapsed()
+    );
+  }
+
+  #[test]
+  fn nesting_stops_at_the_limit() {
+    let nested = format!("{}x{}", "[".repeat(40), "](a)".repeat(40));
+    let html = render(&nested);
+    assert_eq!(MAX_NESTING, html.matches("<a href=\"a\">").count());
+    // what's deeper than that is left as it is
+    assert!(html.contains(&format!("{}x{}", "[".repeat(8), "](a)".repeat(8))));
+  }
+
+  #[test]
+  fn only_web_mail_and_relative_links_are_kept() {
+    for url in [
+      "https://a.org",
+      "HTTP://a.org",
+      "mailto:me@a.org",
+      "/posts/1",
+      "a.html?x=y:z",
+      "#top",
+    ] {
+      assert!(is_safe_url(url), "{}", url);
+    }
+    for url in [
+      "vbscript:x",
+      "data:text/html,x",
+      "file:///etc/passwd",
+      "jav\nascript:x",
+    ] {
+      assert!(!is_safe_url(url), "{}", url);
+    }
+  }
+}
@@ -0,0 +1,300 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+
+// Number of unchanged lines shown around each change in a unified diff.
+const DIFF_CONTEXT: usize = 3;
+
+// A snapshot of a post's content, taken when the post changed its state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Revision {
+  pub number: usize,
+  pub action: String,
+  pub content: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevisionError {
+  NotFound(usize),
+}
+
+impl fmt::Display for RevisionError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      RevisionError::NotFound(number) => write!(f, "revision {} does not exist", number),
+    }
+  }
+}
+
+impl Error for RevisionError {}
+
+// An append-only list of revisions, numbered from 1 in the order they were taken.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RevisionStore {
+  revisions: Vec<Revision>,
+}
+
+impl RevisionStore {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn snapshot(&mut self, action: &str, content: &str) -> usize {
+    let number = self.revisions.len() + 1;
+    self.revisions.push(Revision {
+      number,
+      action: action.to_string(),
+      content: content.to_string(),
+    });
+    number
+  }
+
+  pub fn list(&self) -> &[Revision] {
+    &self.revisions
+  }
+
+  pub fn latest(&self) -> Option<&Revision> {
+    self.revisions.last()
+  }
+
+  pub fn get(&self, number: usize) -> Result<&Revision, RevisionError> {
+    number
+      .checked_sub(1)
+      .and_then(|i| self.revisions.get(i))
+      .ok_or(RevisionError::NotFound(number))
+  }
+
+  // Line-based unified diff going from revision `from` to revision `to`.
+  pub fn diff(&self, from: usize, to: usize) -> Result<String, RevisionError> {
+    let old = self.get(from)?;
+    let new = self.get(to)?;
+
+    Ok(unified_diff(
+      &old.content,
+      &new.content,
+      &format!("revision {} ({})", old.number, old.action),
+      &format!("revision {} ({})", new.number, new.action),
+    ))
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit<'a> {
+  Same(&'a str),
+  Removed(&'a str),
+  Added(&'a str),
+}
+
+// Produces a unified diff (as `diff -u` does) of two texts, compared line by line.
+// Identical texts produce an empty string.
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+  let old_lines: Vec<&str> = old.lines().collect();
+  let new_lines: Vec<&str> = new.lines().collect();
+  let edits = diff_lines(&old_lines, &new_lines);
+
+  let changes: Vec<usize> = edits
+    .iter()
+    .enumerate()
+    .filter(|(_, e)| !matches!(e, Edit::Same(_)))
+    .map(|(i, _)| i)
+    .collect();
+  if changes.is_empty() {
+    return String::new();
+  }
+
+  let mut out = format!("--- {}\n+++ {}\n", old_label, new_label);
+
+  // group changes that are close enough to share their context lines into one hunk
+  let mut groups: Vec<(usize, usize)> = Vec::new();
+  for &i in changes.iter() {
+    match groups.last_mut() {
+      Some((_, last)) if i - *last <= 2 * DIFF_CONTEXT => *last = i,
+      _ => groups.push((i, i)),
+    }
+  }
+
+  for (first, last) in groups {
+    let start = first.saturating_sub(DIFF_CONTEXT);
+    let end = (last + DIFF_CONTEXT + 1).min(edits.len());
+
+    // line positions (0-based) in both texts at the start of the hunk
+    let old_pos = edits[..start]
+      .iter()
+      .filter(|e| !matches!(e, Edit::Added(_)))
+      .count();
+    let new_pos = edits[..start]
+      .iter()
+      .filter(|e| !matches!(e, Edit::Removed(_)))
+      .count();
+    let hunk = &edits[start..end];
+    let old_count = hunk.iter().filter(|e| !matches!(e, Edit::Added(_))).count();
+    let new_count = hunk
+      .iter()
+      .filter(|e| !matches!(e, Edit::Removed(_)))
+      .count();
+
+    out.push_str(&format!(
+      "@@ -{} +{} @@\n",
+      hunk_range(old_pos, old_count),
+      hunk_range(new_pos, new_count)
+    ));
+    for edit in hunk {
+      let (prefix, line) = match edit {
+        Edit::Same(line) => (' ', line),
+        Edit::Removed(line) => ('-', line),
+        Edit::Added(line) => ('+', line),
+      };
+      out.push(prefix);
+      out.push_str(line);
+      out.push('\n');
+    }
+  }
+
+  out
+}
+
+// Hunk ranges are 1-based; an empty range points at the line preceding it.
+fn hunk_range(pos: usize, count: usize) -> String {
+  match count {
+    0 => format!("{},0", pos),
+    1 => format!("{}", pos + 1),
+    _ => format!("{},{}", pos + 1, count),
+  }
+}
+
+// A classic longest-common-subsequence diff; posts are small enough for the O(n*m) table.
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Edit<'a>> {
+  // lcs[i][j] is the length of the LCS of old[i..] and new[j..]
+  let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+  for i in (0..old.len()).rev() {
+    for j in (0..new.len()).rev() {
+      lcs[i][j] = if old[i] == new[j] {
+        lcs[i + 1][j + 1] + 1
+      } else {
+        lcs[i + 1][j].max(lcs[i][j + 1])
+      };
+    }
+  }
+
+  let mut edits = Vec::with_capacity(old.len().max(new.len()));
+  let (mut i, mut j) = (0, 0);
+  while i < old.len() && j < new.len() {
+    if old[i] == new[j] {
+      edits.push(Edit::Same(old[i]));
+      i += 1;
+      j += 1;
+    } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+      edits.push(Edit::Removed(old[i]));
+      i += 1;
+    } else {
+      edits.push(Edit::Added(new[j]));
+      j += 1;
+    }
+  }
+  edits.extend(old[i..].iter().map(|line| Edit::Removed(line)));
+  edits.extend(new[j..].iter().map(|line| Edit::Added(line)));
+
+  edits
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn revisions_are_numbered_from_one() {
+    let mut store = RevisionStore::new();
+    assert_eq!(1, store.snapshot("request_review", "first"));
+    assert_eq!(2, store.snapshot("approve", "second"));
+
+    assert_eq!("second", store.get(2).unwrap().content);
+    assert_eq!(Err(RevisionError::NotFound(0)), store.get(0));
+    assert_eq!(Err(RevisionError::NotFound(3)), store.get(3));
+  }
+
+  #[test]
+  fn identical_texts_have_no_diff() {
+    assert_eq!("", unified_diff("a\nb\n", "a\nb\n", "old", "new"));
+  }
+
+  #[test]
+  fn diff_shows_changed_lines_with_context() {
+    let old = "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\n";
+    let new = "one\ntwo\nthree\nfour\n5\nsix\nseven\neight\nnine\nten\neleven\n";
+
+    let expected = "\
+--- old
++++ new
+@@ -2,9 +2,10 @@
+ two
+ three
+ four
+-five
++5
+ six
+ seven
+ eight
+ nine
+ ten
++eleven
+";
+    assert_eq!(expected, unified_diff(old, new, "old", "new"));
+  }
+
+  #[test]
+  fn distant_changes_get_separate_hunks() {
+    let old: String = (1..=20).map(|n| format!("{}\n", n)).collect();
+    let new: String = (1..=20)
+      .map(|n| match n {
+        2 => "two\n".to_string(),
+        19 => "nineteen\n".to_string(),
+        _ => format!("{}\n", n),
+      })
+      .collect();
+
+    let expected = "\
+--- old
++++ new
+@@ -1,5 +1,5 @@
+ 1
+-2
++two
+ 3
+ 4
+ 5
+@@ -16,5 +16,5 @@
+ 16
+ 17
+ 18
+-19
++nineteen
+ 20
+";
+    assert_eq!(expected, unified_diff(&old, &new, "old", "new"));
+  }
+
+  #[test]
+  fn diff_from_empty_text() {
+    let expected = "\
+--- old
++++ new
+@@ -0,0 +1,2 @@
++hello
++world
+";
+    assert_eq!(expected, unified_diff("", "hello\nworld", "old", "new"));
+  }
+
+  #[test]
+  fn store_diffs_revisions_by_number() {
+    let mut store = RevisionStore::new();
+    store.snapshot("request_review", "I ate a salad");
+    store.snapshot("approve", "I ate a soup");
+
+    let diff = store.diff(1, 2).unwrap();
+    assert!(diff.starts_with("--- revision 1 (request_review)\n+++ revision 2 (approve)\n"));
+    assert!(diff.ends_with("@@ -1 +1 @@\n-I ate a salad\n+I ate a soup\n"));
+    assert_eq!(Err(RevisionError::NotFound(5)), store.diff(1, 5));
+  }
+}
@@ -0,0 +1,206 @@
+use std::error::Error;
+use std::fmt;
+
+// Version of the on-disk representation, written into both the JSON and the text format so that
+// older files can still be recognized once the format evolves.
+pub const FORMAT_VERSION: u32 = 1;
+
+const FRONT_MATTER_FENCE: &str = "---";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageError {
+  Json(String),
+  Malformed(String),
+  MissingField(&'static str),
+  UnsupportedVersion(u32),
+  UnknownState(String),
+  // The stored post is in another state than the type it's being loaded into.
+  StateMismatch {
+    expected: &'static str,
+    found: String,
+  },
+}
+
+impl fmt::Display for StorageError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      StorageError::Json(e) => write!(f, "invalid JSON: {}", e),
+      StorageError::Malformed(e) => write!(f, "malformed post: {}", e),
+      StorageError::MissingField(field) => write!(f, "missing field `{}`", field),
+      StorageError::UnsupportedVersion(v) => write!(f, "unsupported format version {}", v),
+      StorageError::UnknownState(state) => write!(f, "unknown post state `{}`", state),
+      StorageError::StateMismatch { expected, found } => {
+        write!(f, "expected a {} post, found a {} one", expected, found)
+      }
+    }
+  }
+}
+
+impl Error for StorageError {}
+
+impl From<serde_json::Error> for StorageError {
+  fn from(e: serde_json::Error) -> Self {
+    StorageError::Json(e.to_string())
+  }
+}
+
+pub fn check_version(version: u32) -> Result<(), StorageError> {
+  if version == FORMAT_VERSION {
+    Ok(())
+  } else {
+    Err(StorageError::UnsupportedVersion(version))
+  }
+}
+
+// The text format is a front matter block of `key: value` lines, fenced by `---` lines, followed
+// by the post's content verbatim. Keys may repeat (e.g. one `approved-by` line per reviewer).
+//
+//    ---
+//    format: 1
+//    state: PendingReview
+//    ---
+//    I ate a salad for lunch today
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FrontMatter {
+  fields: Vec<(String, String)>,
+}
+
+impl FrontMatter {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  // Values are single-line, so any line breaks are replaced with spaces.
+  pub fn push(&mut self, key: &str, value: impl ToString) {
+    let value = value.to_string().replace(['\r', '\n'], " ");
+    self.fields.push((key.to_string(), value));
+  }
+
+  pub fn get(&self, key: &str) -> Option<&str> {
+    self
+      .fields
+      .iter()
+      .find(|(k, _)| k == key)
+      .map(|(_, v)| v.as_str())
+  }
+
+  pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    self
+      .fields
+      .iter()
+      .filter(move |(k, _)| k == key)
+      .map(|(_, v)| v.as_str())
+  }
+
+  pub fn require(&self, key: &'static str) -> Result<&str, StorageError> {
+    self.get(key).ok_or(StorageError::MissingField(key))
+  }
+
+  pub fn require_number<T: std::str::FromStr>(&self, key: &'static str) -> Result<T, StorageError> {
+    let value = self.require(key)?;
+    value
+      .parse()
+      .map_err(|_| StorageError::Malformed(format!("`{}` is not a number: {}", key, value)))
+  }
+
+  pub fn write(&self, body: &str) -> String {
+    let mut out = format!("{}\n", FRONT_MATTER_FENCE);
+    for (key, value) in self.fields.iter() {
+      out.push_str(&format!("{}: {}\n", key, value));
+    }
+    out.push_str(&format!("{}\n", FRONT_MATTER_FENCE));
+    out.push_str(body);
+    out
+  }
+
+  // Splits a text file into its front matter and its body.
+  pub fn parse(text: &str) -> Result<(FrontMatter, &str), StorageError> {
+    let rest = text
+      .strip_prefix(FRONT_MATTER_FENCE)
+      .and_then(|rest| rest.strip_prefix('\n'))
+      .ok_or_else(|| StorageError::Malformed("missing opening `---` line".to_string()))?;
+
+    let mut front_matter = FrontMatter::new();
+    let mut rest = rest;
+    loop {
+      let (line, next) = match rest.split_once('\n') {
+        Some((line, next)) => (line, next),
+        None => (rest, ""),
+      };
+      if line == FRONT_MATTER_FENCE {
+        return Ok((front_matter, next));
+      }
+      if next.is_empty() && line.is_empty() {
+        return Err(StorageError::Malformed(
+          "missing closing `---` line".to_string(),
+        ));
+      }
+
+      let (key, value) = line
+        .split_once(':')
+        .ok_or_else(|| StorageError::Malformed(format!("expected `key: value`, got `{}`", line)))?;
+      front_matter
+        .fields
+        .push((key.trim().to_string(), value.trim().to_string()));
+      rest = next;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn front_matter_round_trip_keeps_body_verbatim() {
+    let mut fm = FrontMatter::new();
+    fm.push("format", FORMAT_VERSION);
+    fm.push("approved-by", "alice");
+    fm.push("approved-by", "bob");
+    let body = "---\nnot: front matter\n\n";
+
+    let text = fm.write(body);
+    assert_eq!(
+      "---\nformat: 1\napproved-by: alice\napproved-by: bob\n---\n---\nnot: front matter\n\n",
+      text
+    );
+
+    let (parsed, parsed_body) = FrontMatter::parse(&text).unwrap();
+    assert_eq!(fm, parsed);
+    assert_eq!(body, parsed_body);
+    assert_eq!(
+      vec!["alice", "bob"],
+      parsed.get_all("approved-by").collect::<Vec<_>>()
+    );
+    assert_eq!(Ok(1u32), parsed.require_number("format"));
+  }
+
+  #[test]
+  fn front_matter_requires_fences() {
+    assert!(matches!(
+      FrontMatter::parse("state: Draft\n"),
+      Err(StorageError::Malformed(_))
+    ));
+    assert!(matches!(
+      FrontMatter::parse("---\nstate: Draft\n"),
+      Err(StorageError::Malformed(_))
+    ));
+    assert!(matches!(
+      FrontMatter::parse("---\nno separator\n---\n"),
+      Err(StorageError::Malformed(_))
+    ));
+  }
+
+  #[test]
+  fn missing_fields_are_reported_by_name() {
+    let (fm, _) = FrontMatter::parse("---\nformat: x\n---\n").unwrap();
+    assert_eq!(
+      Err(StorageError::MissingField("state")),
+      fm.require("state")
+    );
+    assert!(matches!(
+      fm.require_number::<u32>("format"),
+      Err(StorageError::Malformed(_))
+    ));
+  }
+}
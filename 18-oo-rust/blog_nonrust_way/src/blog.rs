@@ -0,0 +1,110 @@
+// A Post can be kept in a Blog along with many others, whatever state it's in.
+use super::{Post, PostStatusKind};
+use blog_common::blog::BlogPost;
+
+impl BlogPost for Post {
+  // keyed by the kind of status, or each publication time would be a status of its own
+  type Status = PostStatusKind;
+
+  fn status(&self) -> PostStatusKind {
+    Post::status(self).kind()
+  }
+
+  // content() already hides whatever hasn't been published yet
+  fn published_content(&self) -> &str {
+    self.content()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Actor, Blog, ManualClock, PostStatus, TransitionError};
+  use std::sync::Arc;
+
+  fn alice() -> Actor {
+    Actor::author("alice")
+  }
+
+  fn bob() -> Actor {
+    Actor::reviewer("bob")
+  }
+
+  fn post(text: &str, required_approvals: usize) -> Post {
+    let mut post = Post::with_required_approvals(&alice(), required_approvals);
+    post.add_text(&alice(), text).unwrap();
+    post
+  }
+
+  #[test]
+  fn reviewer_approves_all_pending_posts_at_once() {
+    let mut blog = Blog::new();
+    let draft = blog.insert(post("Still a draft", 1));
+    let mut salad = post("I ate a salad for lunch today", 1);
+    salad.request_review(&alice()).unwrap();
+    let salad = blog.insert(salad);
+    let mut soup = post("Soup for dinner", 2);
+    soup.request_review(&alice()).unwrap();
+    let soup = blog.insert(soup);
+
+    let outcomes =
+      blog.update_with_status(PostStatusKind::PendingReview, |post| post.approve(&bob()));
+    assert_eq!(vec![(salad, Ok(())), (soup, Ok(()))], outcomes);
+
+    // soup needs a second approval, so it's still waiting for review
+    assert_eq!(vec![salad], blog.with_status(PostStatusKind::Published));
+    assert_eq!(vec![soup], blog.with_status(PostStatusKind::PendingReview));
+    assert_eq!(vec![draft], blog.with_status(PostStatusKind::Draft));
+
+    // the author can't approve their own posts, and each refusal is reported
+    let outcomes =
+      blog.update_with_status(PostStatusKind::PendingReview, |post| post.approve(&alice()));
+    assert!(matches!(
+      outcomes.as_slice(),
+      [(id, Err(TransitionError::Permission(_)))] if *id == soup
+    ));
+  }
+
+  #[test]
+  fn scheduled_posts_are_listed_together_whenever_theyre_due() {
+    let clock = Arc::new(ManualClock::new(100));
+    let mut blog = Blog::new();
+    let mut scheduled = Vec::new();
+    for publish_at in [200, 300] {
+      let mut post = post("Salad again", 1).with_clock(clock.clone());
+      post.request_review(&alice()).unwrap();
+      post.schedule(&bob(), publish_at).unwrap();
+      post.approve(&bob()).unwrap();
+      assert_eq!(PostStatus::Scheduled { publish_at }, post.status());
+      scheduled.push(blog.insert(post));
+    }
+    blog.insert(post("Still a draft", 1));
+
+    assert_eq!(scheduled, blog.with_status(PostStatusKind::Scheduled));
+    let by_status = blog.by_status();
+    assert_eq!(Some(&scheduled), by_status.get(&PostStatusKind::Scheduled));
+  }
+
+  #[test]
+  fn search_skips_unpublished_posts() {
+    let clock = Arc::new(ManualClock::new(100));
+    let mut blog = Blog::new();
+    let mut published = post("I ate a salad for lunch today", 1);
+    published.request_review(&alice()).unwrap();
+    published.approve(&bob()).unwrap();
+    let published = blog.insert(published);
+
+    let mut scheduled = post("Salad again", 1).with_clock(clock.clone());
+    scheduled.request_review(&alice()).unwrap();
+    scheduled.schedule(&bob(), 200).unwrap();
+    scheduled.approve(&bob()).unwrap();
+    let scheduled = blog.insert(scheduled);
+    blog.insert(post("Secret salad recipe", 1));
+
+    assert_eq!(vec![published], blog.search("salad"));
+
+    clock.set(200);
+    assert_eq!(vec![published, scheduled], blog.search("salad"));
+    assert!(blog.search("secret").is_empty());
+  }
+}
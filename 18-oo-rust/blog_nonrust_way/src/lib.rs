@@ -8,79 +8,584 @@
 //  * Allow users to add text content only when a post is in the Draft state. Hint: have the state object
 //    responsible for what might change about the content but not responsible for modifying the Post.
 
+mod blog;
+mod storage;
+
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+use blog_common::access::authorize;
+use blog_common::markdown;
+
+pub use blog_common::access::{Action, Actor, PermissionError, Policy, Role, RolePolicy};
+pub use blog_common::blog::{Blog, BlogPost, PostId};
+pub use blog_common::clock::{Clock, ManualClock, SystemClock, Timestamp};
+pub use blog_common::revisions::{Revision, RevisionError, RevisionStore};
+pub use blog_common::storage::StorageError;
+
+// A refused transition hands the unchanged state back, together with the reason it was refused.
+pub type TransitionResult = Result<Box<dyn State>, (Box<dyn State>, TransitionError)>;
+
 pub trait State {
   // these intentionally consume state and return a new state...
-  fn request_review(self: Box<Self>) -> Box<dyn State>;
-  fn approve(self: Box<Self>) -> Box<dyn State>;
+  fn request_review(self: Box<Self>) -> TransitionResult;
+  fn approve(self: Box<Self>, reviewer: &str) -> TransitionResult;
+  fn reject(self: Box<Self>) -> TransitionResult;
+  fn schedule(self: Box<Self>, publish_at: Timestamp) -> TransitionResult;
+  fn unschedule(self: Box<Self>) -> TransitionResult;
+  fn publish_due(self: Box<Self>, now: Timestamp) -> TransitionResult;
+
+  // The state object decides whether (and what) text may be added, but it's still the Post
+  // that modifies its own content. Only drafts are editable, hence the default implementation.
+  fn add_text<'a>(&self, _text: &'a str) -> Result<&'a str, TransitionError> {
+    Err(TransitionError::NotEditable(self.status()))
+  }
+
+  // Lets the outside world know which state a post is in, without exposing the state objects.
+  fn status(&self) -> PostStatus;
 
   // A default implementation for the content method that returns an empty string slice,
   // meaning we don’t need to implement content on the Draft and PendingReview structs.
   fn content<'a>(&self, _post: &'a Post) -> &'a str {
     ""
   }
+
+  // Whether the post's content is out for readers to see, and may thus be rendered.
+  fn is_public(&self, _post: &Post) -> bool {
+    false
+  }
+
+  // The approval quorum of the post, so that it can be carried over to a restored draft.
+  fn required_approvals(&self) -> usize;
+
+  // Approvals collected so far; only the states that track them override this.
+  fn approvals(&self) -> &[Approval] {
+    &[]
+  }
+
+  // The requested publication time of a post that isn't published yet.
+  fn publish_at(&self) -> Option<Timestamp> {
+    None
+  }
+}
+
+// What every state answers to an action it doesn't support.
+fn refuse(state: Box<dyn State>, action: Action) -> TransitionResult {
+  let status = state.status();
+  Err((state, TransitionError::InvalidTransition { action, status }))
+}
+
+// The publicly inspectable counterpart of the (private) state objects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PostStatus {
+  Draft,
+  PendingReview,
+  Scheduled { publish_at: Timestamp },
+  Published,
+}
+
+impl PostStatus {
+  pub fn kind(&self) -> PostStatusKind {
+    match self {
+      PostStatus::Draft => PostStatusKind::Draft,
+      PostStatus::PendingReview => PostStatusKind::PendingReview,
+      PostStatus::Scheduled { .. } => PostStatusKind::Scheduled,
+      PostStatus::Published => PostStatusKind::Published,
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    self.kind().name()
+  }
 }
 
-struct Draft {}
-struct PendingReview {}
-struct Published {}
+// The status without what it knows about the post, for asking which status posts are in: all
+// scheduled posts are Scheduled, whenever they're due.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PostStatusKind {
+  Draft,
+  PendingReview,
+  Scheduled,
+  Published,
+}
+
+impl PostStatusKind {
+  pub fn name(&self) -> &'static str {
+    match self {
+      PostStatusKind::Draft => "Draft",
+      PostStatusKind::PendingReview => "PendingReview",
+      PostStatusKind::Scheduled => "Scheduled",
+      PostStatusKind::Published => "Published",
+    }
+  }
+}
+
+// An entry of the post's append-only transition log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transition {
+  pub seq: u64,
+  pub from: PostStatus,
+  pub to: PostStatus,
+  pub action: String,
+}
+
+// Why an operation on a post was refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransitionError {
+  NotEditable(PostStatus),
+  InvalidTransition { action: Action, status: PostStatus },
+  AlreadyApproved { reviewer: String },
+  Permission(PermissionError),
+  Revision(RevisionError),
+}
+
+impl fmt::Display for TransitionError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      TransitionError::NotEditable(status) => {
+        write!(
+          f,
+          "content can only be edited in Draft, post is {}",
+          status.name()
+        )
+      }
+      TransitionError::InvalidTransition { action, status } => {
+        write!(f, "can't {} a post in {}", action.name(), status.name())
+      }
+      TransitionError::AlreadyApproved { reviewer } => {
+        write!(f, "{} has already approved the post", reviewer)
+      }
+      TransitionError::Permission(e) => e.fmt(f),
+      TransitionError::Revision(e) => e.fmt(f),
+    }
+  }
+}
+
+impl Error for TransitionError {}
+
+impl From<PermissionError> for TransitionError {
+  fn from(e: PermissionError) -> Self {
+    TransitionError::Permission(e)
+  }
+}
+
+impl From<RevisionError> for TransitionError {
+  fn from(e: RevisionError) -> Self {
+    TransitionError::Revision(e)
+  }
+}
+
+// A single reviewer's sign-off on a post.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Approval {
+  pub reviewer: String,
+}
+
+// The approval quorum travels with the state objects (rather than living in `Post`), so
+// each state alone decides whether it has collected enough approvals to move on.
+struct Draft {
+  required_approvals: usize,
+}
+
+struct PendingReview {
+  required_approvals: usize,
+  approvals: Vec<Approval>,
+  publish_at: Option<Timestamp>,
+}
+
+// An approved post waiting for its publication time. The content is shown as soon as the post's
+// clock reaches `publish_at`, even before publish_due() moves the post over to Published.
+struct Scheduled {
+  required_approvals: usize,
+  approvals: Vec<Approval>,
+  publish_at: Timestamp,
+}
+
+struct Published {
+  required_approvals: usize,
+  approvals: Vec<Approval>,
+}
 
 impl State for Draft {
-  fn request_review(self: Box<Self>) -> Box<dyn State> {
-    Box::new(PendingReview {})
+  fn request_review(self: Box<Self>) -> TransitionResult {
+    Ok(Box::new(PendingReview {
+      required_approvals: self.required_approvals,
+      approvals: Vec::new(),
+      publish_at: None,
+    }))
   }
 
-  fn approve(self: Box<Self>) -> Box<dyn State> {
-    self
+  fn approve(self: Box<Self>, _reviewer: &str) -> TransitionResult {
+    refuse(self, Action::Approve)
+  }
+
+  fn reject(self: Box<Self>) -> TransitionResult {
+    refuse(self, Action::Reject)
+  }
+
+  fn schedule(self: Box<Self>, _publish_at: Timestamp) -> TransitionResult {
+    refuse(self, Action::Schedule)
+  }
+
+  fn unschedule(self: Box<Self>) -> TransitionResult {
+    refuse(self, Action::Unschedule)
+  }
+
+  fn publish_due(self: Box<Self>, _now: Timestamp) -> TransitionResult {
+    refuse(self, Action::Publish)
+  }
+
+  fn add_text<'a>(&self, text: &'a str) -> Result<&'a str, TransitionError> {
+    Ok(text)
+  }
+
+  fn status(&self) -> PostStatus {
+    PostStatus::Draft
+  }
+
+  fn required_approvals(&self) -> usize {
+    self.required_approvals
   }
 }
 
 impl State for PendingReview {
-  fn request_review(self: Box<Self>) -> Box<dyn State> {
-    self
+  fn request_review(self: Box<Self>) -> TransitionResult {
+    refuse(self, Action::RequestReview)
+  }
+
+  // Only distinct reviewers count towards the quorum; approving twice is refused.
+  fn approve(mut self: Box<Self>, reviewer: &str) -> TransitionResult {
+    if self.approvals.iter().any(|a| a.reviewer == reviewer) {
+      let reviewer = reviewer.to_string();
+      return Err((self, TransitionError::AlreadyApproved { reviewer }));
+    }
+
+    self.approvals.push(Approval {
+      reviewer: reviewer.to_string(),
+    });
+    if self.approvals.len() < self.required_approvals {
+      return Ok(self);
+    }
+
+    match self.publish_at {
+      Some(publish_at) => Ok(Box::new(Scheduled {
+        required_approvals: self.required_approvals,
+        approvals: self.approvals,
+        publish_at,
+      })),
+      None => Ok(Box::new(Published {
+        required_approvals: self.required_approvals,
+        approvals: self.approvals,
+      })),
+    }
+  }
+
+  // Rejection sends the post back to Draft and discards approvals of this review round.
+  fn reject(self: Box<Self>) -> TransitionResult {
+    Ok(Box::new(Draft {
+      required_approvals: self.required_approvals,
+    }))
+  }
+
+  // Asks for the post to go live at a given time once it gets approved, instead of immediately.
+  fn schedule(mut self: Box<Self>, publish_at: Timestamp) -> TransitionResult {
+    self.publish_at = Some(publish_at);
+    Ok(self)
+  }
+
+  fn unschedule(mut self: Box<Self>) -> TransitionResult {
+    self.publish_at = None;
+    Ok(self)
   }
 
-  fn approve(self: Box<Self>) -> Box<dyn State> {
-    Box::new(Published {})
+  fn publish_due(self: Box<Self>, _now: Timestamp) -> TransitionResult {
+    refuse(self, Action::Publish)
+  }
+
+  fn status(&self) -> PostStatus {
+    PostStatus::PendingReview
+  }
+
+  fn required_approvals(&self) -> usize {
+    self.required_approvals
+  }
+
+  fn approvals(&self) -> &[Approval] {
+    &self.approvals
+  }
+
+  fn publish_at(&self) -> Option<Timestamp> {
+    self.publish_at
+  }
+}
+
+impl State for Scheduled {
+  fn request_review(self: Box<Self>) -> TransitionResult {
+    refuse(self, Action::RequestReview)
+  }
+
+  fn approve(self: Box<Self>, _reviewer: &str) -> TransitionResult {
+    refuse(self, Action::Approve)
+  }
+
+  fn reject(self: Box<Self>) -> TransitionResult {
+    refuse(self, Action::Reject)
+  }
+
+  // Moves the post to another time, it stays approved.
+  fn schedule(mut self: Box<Self>, publish_at: Timestamp) -> TransitionResult {
+    self.publish_at = publish_at;
+    Ok(self)
+  }
+
+  // Unscheduling sends the post back to review, which has to approve it again.
+  fn unschedule(self: Box<Self>) -> TransitionResult {
+    Ok(Box::new(PendingReview {
+      required_approvals: self.required_approvals,
+      approvals: Vec::new(),
+      publish_at: None,
+    }))
+  }
+
+  // Not being due yet isn't an error: the post simply stays scheduled.
+  fn publish_due(self: Box<Self>, now: Timestamp) -> TransitionResult {
+    if now >= self.publish_at {
+      Ok(Box::new(Published {
+        required_approvals: self.required_approvals,
+        approvals: self.approvals,
+      }))
+    } else {
+      Ok(self)
+    }
+  }
+
+  fn status(&self) -> PostStatus {
+    PostStatus::Scheduled {
+      publish_at: self.publish_at,
+    }
+  }
+
+  fn content<'a>(&self, post: &'a Post) -> &'a str {
+    if self.is_public(post) {
+      &post.content
+    } else {
+      ""
+    }
+  }
+
+  fn is_public(&self, post: &Post) -> bool {
+    post.clock.now() >= self.publish_at
+  }
+
+  fn required_approvals(&self) -> usize {
+    self.required_approvals
+  }
+
+  fn approvals(&self) -> &[Approval] {
+    &self.approvals
+  }
+
+  fn publish_at(&self) -> Option<Timestamp> {
+    Some(self.publish_at)
   }
 }
 
 impl State for Published {
-  fn request_review(self: Box<Self>) -> Box<dyn State> {
-    self
+  fn request_review(self: Box<Self>) -> TransitionResult {
+    refuse(self, Action::RequestReview)
   }
 
-  fn approve(self: Box<Self>) -> Box<dyn State> {
-    self
+  fn approve(self: Box<Self>, _reviewer: &str) -> TransitionResult {
+    refuse(self, Action::Approve)
+  }
+
+  fn reject(self: Box<Self>) -> TransitionResult {
+    refuse(self, Action::Reject)
+  }
+
+  fn schedule(self: Box<Self>, _publish_at: Timestamp) -> TransitionResult {
+    refuse(self, Action::Schedule)
+  }
+
+  fn unschedule(self: Box<Self>) -> TransitionResult {
+    refuse(self, Action::Unschedule)
+  }
+
+  fn publish_due(self: Box<Self>, _now: Timestamp) -> TransitionResult {
+    refuse(self, Action::Publish)
+  }
+
+  fn status(&self) -> PostStatus {
+    PostStatus::Published
+  }
+
+  fn required_approvals(&self) -> usize {
+    self.required_approvals
   }
 
   fn content<'a>(&self, post: &'a Post) -> &'a str {
     &post.content
   }
+
+  fn is_public(&self, _post: &Post) -> bool {
+    true
+  }
+
+  fn approvals(&self) -> &[Approval] {
+    &self.approvals
+  }
 }
 
 pub struct Post {
   state: Option<Box<dyn State>>,
   content: String,
+  author: String,
+  history: Vec<Transition>,
+  revisions: RevisionStore,
+  clock: Arc<dyn Clock>,
+  policy: Arc<dyn Policy>,
 }
 
 impl Post {
-  pub fn new() -> Post {
+  pub fn new(author: &Actor) -> Post {
+    Self::with_required_approvals(author, 1)
+  }
+
+  // A post needs at least one approval to get published, so a quorum of 0 is treated as 1.
+  pub fn with_required_approvals(author: &Actor, required_approvals: usize) -> Post {
     Self {
-      state: Some(Box::new(Draft {})),
+      state: Some(Box::new(Draft {
+        required_approvals: required_approvals.max(1),
+      })),
       content: String::new(),
+      author: author.name.clone(),
+      history: Vec::new(),
+      revisions: RevisionStore::new(),
+      clock: Arc::new(SystemClock),
+      policy: Arc::new(RolePolicy),
     }
   }
 
+  // Replaces the system clock, which decides when a scheduled post goes live.
+  pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Post {
+    self.clock = clock;
+    self
+  }
+
+  // Replaces the default RolePolicy deciding who may take the post through which transition.
+  pub fn with_policy(mut self, policy: Arc<dyn Policy>) -> Post {
+    self.policy = policy;
+    self
+  }
+
   pub fn content(&self) -> &str {
     self.state.as_ref().unwrap().content(self)
   }
 
-  pub fn add_text(&mut self, text: &str) {
+  // The content rendered from Markdown to HTML, for published posts only.
+  pub fn html(&self) -> Option<String> {
+    if self.state.as_ref().unwrap().is_public(self) {
+      Some(markdown::render(&self.content))
+    } else {
+      None
+    }
+  }
+
+  pub fn author(&self) -> &str {
+    &self.author
+  }
+
+  pub fn approvals(&self) -> &[Approval] {
+    self.state.as_ref().unwrap().approvals()
+  }
+
+  pub fn status(&self) -> PostStatus {
+    self.state.as_ref().unwrap().status()
+  }
+
+  pub fn publish_at(&self) -> Option<Timestamp> {
+    self.state.as_ref().unwrap().publish_at()
+  }
+
+  // Every state change the post went through, oldest first.
+  pub fn history(&self) -> &[Transition] {
+    &self.history
+  }
+
+  // Content snapshots taken at each state transition, for editorial audits.
+  pub fn revisions(&self) -> &RevisionStore {
+    &self.revisions
+  }
+
+  // Starts a new draft from the content of an older revision. This post stays as it is, while
+  // the new draft inherits its author, revisions, approval quorum, clock and policy.
+  pub fn restore(&self, actor: &Actor, number: usize) -> Result<Post, TransitionError> {
+    authorize(self.policy.as_ref(), actor, Action::Restore, &self.author)?;
+    let revision = self.revisions.get(number)?;
+
+    let mut post = Post {
+      state: Some(Box::new(Draft {
+        required_approvals: self.state.as_ref().unwrap().required_approvals(),
+      })),
+      content: revision.content.clone(),
+      author: self.author.clone(),
+      history: Vec::new(),
+      revisions: self.revisions.clone(),
+      clock: Arc::clone(&self.clock),
+      policy: Arc::clone(&self.policy),
+    };
+    post
+      .revisions
+      .snapshot(&format!("restore {}", number), &post.content);
+    Ok(post)
+  }
+
+  pub fn add_text(&mut self, actor: &Actor, text: &str) -> Result<(), TransitionError> {
+    authorize(self.policy.as_ref(), actor, Action::Edit, &self.author)?;
+    let text = self.state.as_ref().unwrap().add_text(text)?;
     // self.content = text.to_string();
     self.content.push_str(text);
+    Ok(())
+  }
+
+  pub fn request_review(&mut self, actor: &Actor) -> Result<(), TransitionError> {
+    self.transition(actor, Action::RequestReview, |s| s.request_review())
   }
 
-  pub fn request_review(&mut self) {
+  pub fn approve(&mut self, reviewer: &Actor) -> Result<(), TransitionError> {
+    self.transition(reviewer, Action::Approve, |s| s.approve(&reviewer.name))
+  }
+
+  pub fn reject(&mut self, reviewer: &Actor) -> Result<(), TransitionError> {
+    self.transition(reviewer, Action::Reject, |s| s.reject())
+  }
+
+  // A post pending review gets scheduled, rather than published, once approved. A scheduled
+  // post can be moved to another time.
+  pub fn schedule(&mut self, actor: &Actor, publish_at: Timestamp) -> Result<(), TransitionError> {
+    self.transition(actor, Action::Schedule, |s| s.schedule(publish_at))
+  }
+
+  pub fn unschedule(&mut self, actor: &Actor) -> Result<(), TransitionError> {
+    self.transition(actor, Action::Unschedule, |s| s.unschedule())
+  }
+
+  // Moves a scheduled post over to Published once its publication time has come.
+  pub fn publish_due(&mut self, actor: &Actor) -> Result<(), TransitionError> {
+    let now = self.clock.now();
+    self.transition(actor, Action::Publish, |s| s.publish_due(now))
+  }
+
+  fn transition(
+    &mut self,
+    actor: &Actor,
+    action: Action,
+    f: impl FnOnce(Box<dyn State>) -> TransitionResult,
+  ) -> Result<(), TransitionError> {
+    authorize(self.policy.as_ref(), actor, action, &self.author)?;
+
     // We call the take() method to take the `Some` value out of the `state` field and
     // leave a `None` in its place, because Rust doesn’t let us have unpopulated fields
     // in structs. -- That's why `state` is defined as `Option<T>`
@@ -89,16 +594,479 @@ impl Post {
     // -------------------------------------------------------------------------------------
     // !!! This ensures Post can’t use the old state value in any way (a separate reference)
     // after we’ve transformed it into a new state. !!!
-    if let Some(s) = self.state.take() {
-      self.state = Some(s.request_review());
+    let s = self.state.take().unwrap();
+    let from = s.status();
+    let (next, result) = match f(s) {
+      Ok(next) => (next, Ok(())),
+      Err((unchanged, e)) => (unchanged, Err(e)),
+    };
+    let to = next.status();
+    self.state = Some(next);
+
+    // Only actual state changes are logged, e.g. a partial approval isn't one. Scheduling is
+    // logged whenever it goes through though, as scheduling a post pending review changes its
+    // publication time without changing its status.
+    let scheduling = matches!(action, Action::Schedule | Action::Unschedule);
+    if from != to || (scheduling && result.is_ok()) {
+      self.revisions.snapshot(action.name(), &self.content);
+      self.history.push(Transition {
+        seq: self.history.len() as u64 + 1,
+        from,
+        to,
+        action: action.name().to_string(),
+      });
     }
+    result
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn alice() -> Actor {
+    Actor::author("alice")
+  }
+
+  fn bob() -> Actor {
+    Actor::reviewer("bob")
+  }
+
+  fn carol() -> Actor {
+    Actor::reviewer("carol")
+  }
+
+  fn pending_post(required_approvals: usize) -> Post {
+    let mut post = Post::with_required_approvals(&alice(), required_approvals);
+    post
+      .add_text(&alice(), "I ate a salad for lunch today")
+      .unwrap();
+    post.request_review(&alice()).unwrap();
+    post
+  }
+
+  #[test]
+  fn single_approval_publishes_by_default() {
+    let mut post = Post::new(&alice());
+    post
+      .add_text(&alice(), "I ate a salad for lunch today")
+      .unwrap();
+    post.request_review(&alice()).unwrap();
+    post.approve(&bob()).unwrap();
+
+    assert_eq!("I ate a salad for lunch today", post.content());
+  }
+
+  #[test]
+  fn reject_sends_post_back_to_draft() {
+    let mut post = pending_post(1);
+    post.reject(&bob()).unwrap();
+    assert_eq!("", post.content());
+
+    // a draft can't be approved, it has to be sent to review again first
+    assert_eq!(
+      Err(TransitionError::InvalidTransition {
+        action: Action::Approve,
+        status: PostStatus::Draft,
+      }),
+      post.approve(&bob())
+    );
+    assert_eq!("", post.content());
+
+    post.request_review(&alice()).unwrap();
+    post.approve(&bob()).unwrap();
+    assert_eq!("I ate a salad for lunch today", post.content());
+  }
+
+  #[test]
+  fn quorum_requires_distinct_reviewers() {
+    let mut post = pending_post(2);
+
+    post.approve(&bob()).unwrap();
+    assert_eq!(
+      Err(TransitionError::AlreadyApproved {
+        reviewer: "bob".to_string()
+      }),
+      post.approve(&bob())
+    );
+    assert_eq!("", post.content());
+    assert_eq!(1, post.approvals().len());
+
+    post.approve(&carol()).unwrap();
+    assert_eq!("I ate a salad for lunch today", post.content());
+  }
+
+  #[test]
+  fn approvals_record_reviewers() {
+    let mut post = pending_post(2);
+    post.approve(&bob()).unwrap();
+    post.approve(&carol()).unwrap();
+
+    let reviewers: Vec<&str> = post
+      .approvals()
+      .iter()
+      .map(|a| a.reviewer.as_str())
+      .collect();
+    assert_eq!(vec!["bob", "carol"], reviewers);
+  }
+
+  #[test]
+  fn reject_discards_collected_approvals() {
+    let mut post = pending_post(2);
+    post.approve(&bob()).unwrap();
+    post.reject(&carol()).unwrap();
+    assert!(post.approvals().is_empty());
+
+    post.request_review(&alice()).unwrap();
+    post.approve(&bob()).unwrap();
+    assert_eq!("", post.content());
+  }
+
+  #[test]
+  fn zero_quorum_still_needs_one_approval() {
+    let mut post = pending_post(0);
+    assert_eq!("", post.content());
+
+    post.approve(&bob()).unwrap();
+    assert_eq!("I ate a salad for lunch today", post.content());
+  }
+
+  #[test]
+  fn status_follows_transitions() {
+    let mut post = Post::new(&alice());
+    assert_eq!(PostStatus::Draft, post.status());
+
+    post.request_review(&alice()).unwrap();
+    assert_eq!(PostStatus::PendingReview, post.status());
+
+    post.reject(&bob()).unwrap();
+    assert_eq!(PostStatus::Draft, post.status());
+
+    post.request_review(&alice()).unwrap();
+    post.approve(&bob()).unwrap();
+    assert_eq!(PostStatus::Published, post.status());
+  }
+
+  #[test]
+  fn history_logs_state_changes_in_order() {
+    let mut post = pending_post(2);
+    post.approve(&bob()).unwrap();
+    post.reject(&carol()).unwrap();
+    post.request_review(&alice()).unwrap();
+    post.approve(&bob()).unwrap();
+    post.approve(&carol()).unwrap();
+
+    let log: Vec<(u64, PostStatus, PostStatus, &str)> = post
+      .history()
+      .iter()
+      .map(|t| (t.seq, t.from, t.to, t.action.as_str()))
+      .collect();
+
+    use PostStatus::*;
+    assert_eq!(
+      vec![
+        (1, Draft, PendingReview, "request_review"),
+        (2, PendingReview, Draft, "reject"),
+        (3, Draft, PendingReview, "request_review"),
+        (4, PendingReview, Published, "approve"),
+      ],
+      log
+    );
   }
 
-  pub fn approve(&mut self) {
-    if let Some(s) = self.state.take() {
-      self.state = Some(s.approve());
+  #[test]
+  fn refused_transitions_are_not_logged() {
+    let mut post = Post::new(&alice());
+    assert!(post.approve(&bob()).is_err());
+    assert!(post.reject(&bob()).is_err());
+    assert!(post.history().is_empty());
+    assert_eq!(PostStatus::Draft, post.status());
+  }
+
+  #[test]
+  fn published_post_refuses_further_transitions() {
+    let mut post = pending_post(1);
+    post.approve(&bob()).unwrap();
+
+    assert_eq!(
+      Err(TransitionError::InvalidTransition {
+        action: Action::Approve,
+        status: PostStatus::Published,
+      }),
+      post.approve(&carol())
+    );
+    assert!(post.reject(&bob()).is_err());
+    assert_eq!("I ate a salad for lunch today", post.content());
+  }
+
+  #[test]
+  fn transitions_are_checked_against_the_policy() {
+    let mut post = Post::new(&alice());
+    assert_eq!(
+      Err(TransitionError::Permission(PermissionError::Forbidden {
+        actor: "bob".to_string(),
+        action: Action::RequestReview,
+      })),
+      post.request_review(&bob())
+    );
+
+    // somebody else's post can't be sent to review by another author
+    let dave = Actor::author("dave");
+    assert!(post.request_review(&dave).is_err());
+
+    post.request_review(&alice()).unwrap();
+    assert!(matches!(
+      post.reject(&alice()),
+      Err(TransitionError::Permission(
+        PermissionError::Forbidden { .. }
+      ))
+    ));
+    assert_eq!(PostStatus::PendingReview, post.status());
+  }
+
+  #[test]
+  fn authors_never_approve_their_own_posts() {
+    struct AnythingGoes;
+
+    impl Policy for AnythingGoes {
+      fn permits(&self, _actor: &Actor, _action: Action, _author: &str) -> bool {
+        true
+      }
     }
+
+    let root = Actor::admin("root");
+    let mut post = Post::new(&root).with_policy(Arc::new(AnythingGoes));
+    post.request_review(&root).unwrap();
+
+    assert_eq!(
+      Err(TransitionError::Permission(PermissionError::SelfApproval {
+        actor: "root".to_string()
+      })),
+      post.approve(&root)
+    );
+    post.approve(&alice()).unwrap();
+    assert_eq!(PostStatus::Published, post.status());
+  }
+
+  #[test]
+  fn text_can_be_added_only_in_draft() {
+    let mut post = pending_post(1);
+    assert_eq!(
+      Err(TransitionError::NotEditable(PostStatus::PendingReview)),
+      post.add_text(&alice(), " and a soup")
+    );
+
+    post.approve(&bob()).unwrap();
+    assert_eq!(
+      Err(TransitionError::NotEditable(PostStatus::Published)),
+      post.add_text(&alice(), " and a soup")
+    );
+    assert_eq!("I ate a salad for lunch today", post.content());
+  }
+
+  #[test]
+  fn only_the_author_edits_a_draft() {
+    let mut post = Post::new(&alice());
+    assert_eq!(
+      Err(TransitionError::Permission(PermissionError::Forbidden {
+        actor: "bob".to_string(),
+        action: Action::Edit,
+      })),
+      post.add_text(&bob(), "I ate a salad")
+    );
+    assert!(post
+      .add_text(&Actor::author("dave"), "I ate a salad")
+      .is_err());
+    post
+      .add_text(&Actor::admin("root"), "I ate a salad")
+      .unwrap();
+    assert_eq!("I ate a salad", post.content.as_str());
   }
 
-  // pub fn state(&self) -> State ...?
+  #[test]
+  fn rejected_post_is_editable_again() {
+    let mut post = pending_post(1);
+    post.reject(&bob()).unwrap();
+    assert_eq!(Ok(()), post.add_text(&alice(), " and a soup"));
+
+    post.request_review(&alice()).unwrap();
+    post.approve(&bob()).unwrap();
+    assert_eq!("I ate a salad for lunch today and a soup", post.content());
+  }
+
+  #[test]
+  fn revisions_are_taken_at_each_state_change() {
+    let mut post = pending_post(1);
+    post.reject(&bob()).unwrap();
+    post.add_text(&alice(), " and a soup").unwrap();
+    post.request_review(&alice()).unwrap();
+    post.approve(&bob()).unwrap();
+
+    let revisions: Vec<(&str, &str)> = post
+      .revisions()
+      .list()
+      .iter()
+      .map(|r| (r.action.as_str(), r.content.as_str()))
+      .collect();
+    assert_eq!(
+      vec![
+        ("request_review", "I ate a salad for lunch today"),
+        ("reject", "I ate a salad for lunch today"),
+        ("request_review", "I ate a salad for lunch today and a soup"),
+        ("approve", "I ate a salad for lunch today and a soup"),
+      ],
+      revisions
+    );
+
+    let diff = post.revisions().diff(1, 4).unwrap();
+    assert!(
+      diff.ends_with("-I ate a salad for lunch today\n+I ate a salad for lunch today and a soup\n")
+    );
+  }
+
+  #[test]
+  fn restore_starts_a_new_draft_from_an_older_revision() {
+    let mut post = pending_post(2);
+    post.reject(&bob()).unwrap();
+    post.add_text(&alice(), " and a soup").unwrap();
+    post.request_review(&alice()).unwrap();
+    post.approve(&bob()).unwrap();
+    post.approve(&carol()).unwrap();
+
+    let mut restored = post.restore(&alice(), 1).unwrap();
+    assert_eq!(PostStatus::Draft, restored.status());
+    assert_eq!("alice", restored.author());
+    assert_eq!("I ate a salad for lunch today and a soup", post.content());
+    assert_eq!(5, restored.revisions().list().len());
+    assert_eq!("restore 1", restored.revisions().latest().unwrap().action);
+
+    // the restored draft keeps the quorum of the original post
+    restored.request_review(&alice()).unwrap();
+    restored.approve(&bob()).unwrap();
+    assert_eq!("", restored.content());
+    restored.approve(&carol()).unwrap();
+    assert_eq!("I ate a salad for lunch today", restored.content());
+
+    assert!(matches!(
+      post.restore(&alice(), 9),
+      Err(TransitionError::Revision(RevisionError::NotFound(9)))
+    ));
+    assert!(matches!(
+      post.restore(&Actor::author("dave"), 1),
+      Err(TransitionError::Permission(_))
+    ));
+  }
+
+  fn scheduled_post(clock: &Arc<ManualClock>, publish_at: Timestamp) -> Post {
+    let mut post = Post::new(&alice()).with_clock(clock.clone());
+    post
+      .add_text(&alice(), "I ate a salad for lunch today")
+      .unwrap();
+    post.request_review(&alice()).unwrap();
+    post.schedule(&bob(), publish_at).unwrap();
+    post.approve(&bob()).unwrap();
+    post
+  }
+
+  #[test]
+  fn scheduled_post_goes_live_when_the_clock_says_so() {
+    let clock = Arc::new(ManualClock::new(1_000));
+    let mut post = scheduled_post(&clock, 1_100);
+    assert_eq!(PostStatus::Scheduled { publish_at: 1_100 }, post.status());
+    assert_eq!("", post.content());
+
+    clock.advance(99);
+    post.publish_due(&bob()).unwrap();
+    assert_eq!("", post.content());
+    assert_eq!(Some(1_100), post.publish_at());
+
+    clock.advance(1);
+    assert_eq!("I ate a salad for lunch today", post.content());
+    post.publish_due(&bob()).unwrap();
+    assert_eq!(PostStatus::Published, post.status());
+    assert_eq!("publish", post.history().last().unwrap().action);
+  }
+
+  #[test]
+  fn unscheduling_sends_post_back_to_review() {
+    let clock = Arc::new(ManualClock::new(1_000));
+    let mut post = scheduled_post(&clock, 2_000);
+
+    post.unschedule(&bob()).unwrap();
+    assert_eq!(PostStatus::PendingReview, post.status());
+    assert!(post.approvals().is_empty());
+    assert_eq!(None, post.publish_at());
+
+    clock.set(3_000);
+    assert!(post.publish_due(&bob()).is_err());
+    assert_eq!("", post.content());
+
+    // without a schedule, approving publishes right away
+    post.approve(&bob()).unwrap();
+    assert_eq!(PostStatus::Published, post.status());
+  }
+
+  #[test]
+  fn scheduled_post_can_be_moved() {
+    let clock = Arc::new(ManualClock::new(1_000));
+    let mut post = scheduled_post(&clock, 5_000);
+
+    post.schedule(&bob(), 1_500).unwrap();
+    clock.set(1_500);
+    assert_eq!("I ate a salad for lunch today", post.content());
+  }
+
+  #[test]
+  fn every_scheduling_is_logged() {
+    let clock = Arc::new(ManualClock::new(1_000));
+    let mut post = scheduled_post(&clock, 5_000);
+    post.schedule(&bob(), 1_500).unwrap();
+    post.unschedule(&bob()).unwrap();
+
+    let log: Vec<(u64, PostStatus, PostStatus, &str)> = post
+      .history()
+      .iter()
+      .map(|t| (t.seq, t.from, t.to, t.action.as_str()))
+      .collect();
+
+    use PostStatus::*;
+    assert_eq!(
+      vec![
+        (1, Draft, PendingReview, "request_review"),
+        (2, PendingReview, PendingReview, "schedule"),
+        (3, PendingReview, Scheduled { publish_at: 5_000 }, "approve"),
+        (
+          4,
+          Scheduled { publish_at: 5_000 },
+          Scheduled { publish_at: 1_500 },
+          "schedule"
+        ),
+        (
+          5,
+          Scheduled { publish_at: 1_500 },
+          PendingReview,
+          "unschedule"
+        ),
+      ],
+      log
+    );
+  }
+
+  #[test]
+  fn only_published_posts_are_rendered() {
+    let mut post = Post::new(&alice());
+    post
+      .add_text(&alice(), "# Lunch\n\nI ate a *salad* <today>")
+      .unwrap();
+    assert_eq!(None, post.html());
+
+    post.request_review(&alice()).unwrap();
+    assert_eq!(None, post.html());
+
+    post.approve(&bob()).unwrap();
+    assert_eq!(
+      Some("<h1>Lunch</h1>\n<p>I ate a <em>salad</em> &lt;today&gt;</p>\n".to_string()),
+      post.html()
+    );
+  }
 }
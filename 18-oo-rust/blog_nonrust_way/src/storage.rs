@@ -0,0 +1,336 @@
+// Persisting a post means persisting its (private) state object too. We can't serialize a
+// `Box<dyn State>` directly, so we store the post's status alongside everything its state object
+// keeps (the approval quorum, the approvals collected so far and the publication time), and
+// rebuild the state object from that when loading the post back.
+use super::{Approval, Draft, PendingReview, Post, Published, Scheduled, State, Transition};
+use blog_common::access::RolePolicy;
+use blog_common::clock::{SystemClock, Timestamp};
+use blog_common::revisions::RevisionStore;
+use blog_common::storage::{check_version, FrontMatter, StorageError, FORMAT_VERSION};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Serialize, Deserialize)]
+struct PostRecord {
+  version: u32,
+  state: String,
+  author: String,
+  required_approvals: usize,
+  #[serde(default)]
+  approvals: Vec<Approval>,
+  #[serde(default)]
+  publish_at: Option<Timestamp>,
+  content: String,
+  #[serde(default)]
+  history: Vec<Transition>,
+  #[serde(default)]
+  revisions: RevisionStore,
+}
+
+// The state's name tells us which state object to rebuild; the other fields must be consistent
+// with what that state object would have allowed, or loading a file would be a way around the
+// review. `publish_at_key` is what the publication time is called in the format being loaded.
+fn rebuild_state(
+  state: &str,
+  required_approvals: usize,
+  approvals: Vec<Approval>,
+  publish_at: Option<Timestamp>,
+  publish_at_key: &'static str,
+) -> Result<Box<dyn State>, StorageError> {
+  let malformed = |reason: &str| Err(StorageError::Malformed(reason.to_string()));
+  if required_approvals == 0 {
+    return malformed("approval quorum must be at least 1");
+  }
+
+  let approved = approvals.len() >= required_approvals;
+  match state {
+    "Draft" | "Published" if publish_at.is_some() => malformed(&format!(
+      "a post in {} can't have `{}`",
+      state, publish_at_key
+    )),
+    "Scheduled" | "Published" if !approved => malformed(&format!(
+      "a post in {} must have reached its approval quorum",
+      state
+    )),
+    "Draft" if !approvals.is_empty() => malformed("a draft can't have approvals"),
+    "Draft" => Ok(Box::new(Draft { required_approvals })),
+    "PendingReview" if approvals.len() >= required_approvals => {
+      malformed("a post pending review can't have reached its approval quorum")
+    }
+    "PendingReview" => Ok(Box::new(PendingReview {
+      required_approvals,
+      approvals,
+      publish_at,
+    })),
+    "Scheduled" => match publish_at {
+      Some(publish_at) => Ok(Box::new(Scheduled {
+        required_approvals,
+        approvals,
+        publish_at,
+      })),
+      None => Err(StorageError::MissingField(publish_at_key)),
+    },
+    "Published" => Ok(Box::new(Published {
+      required_approvals,
+      approvals,
+    })),
+    _ => Err(StorageError::UnknownState(state.to_string())),
+  }
+}
+
+impl Post {
+  fn state_ref(&self) -> &dyn State {
+    self.state.as_deref().unwrap()
+  }
+
+  // The full representation of the post, including its transition log and revisions.
+  pub fn to_json(&self) -> String {
+    let record = PostRecord {
+      version: FORMAT_VERSION,
+      state: self.status().name().to_string(),
+      author: self.author.clone(),
+      required_approvals: self.state_ref().required_approvals(),
+      approvals: self.approvals().to_vec(),
+      publish_at: self.publish_at(),
+      content: self.content.clone(),
+      history: self.history.clone(),
+      revisions: self.revisions.clone(),
+    };
+    // serializing plain strings, numbers and vectors of them can't fail
+    serde_json::to_string_pretty(&record).unwrap()
+  }
+
+  // A loaded post uses the system clock and the default policy, see with_clock() and
+  // with_policy() to replace them.
+  pub fn from_json(json: &str) -> Result<Post, StorageError> {
+    let record: PostRecord = serde_json::from_str(json)?;
+    check_version(record.version)?;
+
+    let state = rebuild_state(
+      &record.state,
+      record.required_approvals,
+      record.approvals,
+      record.publish_at,
+      "publish_at",
+    )?;
+    Ok(Post {
+      state: Some(state),
+      content: record.content,
+      author: record.author,
+      history: record.history,
+      revisions: record.revisions,
+      clock: Arc::new(SystemClock),
+      policy: Arc::new(RolePolicy),
+    })
+  }
+
+  // The text format only keeps the current state and the content: a restored post starts with
+  // an empty transition log and no revisions. Use JSON when those matter.
+  pub fn to_text(&self) -> String {
+    let mut front_matter = FrontMatter::new();
+    front_matter.push("format", FORMAT_VERSION);
+    front_matter.push("state", self.status().name());
+    front_matter.push("author", &self.author);
+    front_matter.push("required-approvals", self.state_ref().required_approvals());
+    for approval in self.approvals() {
+      front_matter.push("approved-by", &approval.reviewer);
+    }
+    if let Some(publish_at) = self.publish_at() {
+      front_matter.push("publish-at", publish_at);
+    }
+    front_matter.write(&self.content)
+  }
+
+  pub fn from_text(text: &str) -> Result<Post, StorageError> {
+    let (front_matter, body) = FrontMatter::parse(text)?;
+    check_version(front_matter.require_number("format")?)?;
+
+    let approvals = front_matter
+      .get_all("approved-by")
+      .map(|reviewer| Approval {
+        reviewer: reviewer.to_string(),
+      })
+      .collect();
+    let publish_at = match front_matter.get("publish-at") {
+      Some(_) => Some(front_matter.require_number("publish-at")?),
+      None => None,
+    };
+    let state = rebuild_state(
+      front_matter.require("state")?,
+      front_matter.require_number("required-approvals")?,
+      approvals,
+      publish_at,
+      "publish-at",
+    )?;
+
+    Ok(Post {
+      state: Some(state),
+      content: body.to_string(),
+      author: front_matter.require("author")?.to_string(),
+      history: Vec::new(),
+      revisions: RevisionStore::new(),
+      clock: Arc::new(SystemClock),
+      policy: Arc::new(RolePolicy),
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Actor, PostStatus};
+
+  fn half_approved_post() -> Post {
+    let mut post = Post::with_required_approvals(&Actor::author("alice"), 2);
+    post
+      .add_text(&Actor::author("alice"), "I ate a salad\nfor lunch today")
+      .unwrap();
+    post.request_review(&Actor::author("alice")).unwrap();
+    post.approve(&Actor::reviewer("bob")).unwrap();
+    post
+  }
+
+  #[test]
+  fn json_round_trip_keeps_state_and_history() {
+    let post = half_approved_post();
+    let mut restored = Post::from_json(&post.to_json()).unwrap();
+
+    assert_eq!(PostStatus::PendingReview, restored.status());
+    assert_eq!("alice", restored.author());
+    assert_eq!(post.approvals(), restored.approvals());
+    assert_eq!(post.history(), restored.history());
+    assert_eq!(post.revisions(), restored.revisions());
+
+    // the restored post continues exactly where the stored one stopped
+    assert!(restored.approve(&Actor::reviewer("bob")).is_err());
+    assert_eq!("", restored.content());
+    restored.approve(&Actor::reviewer("carol")).unwrap();
+    assert_eq!("I ate a salad\nfor lunch today", restored.content());
+    assert_eq!(2, restored.history().len());
+  }
+
+  #[test]
+  fn text_format_has_front_matter_and_body() {
+    let post = half_approved_post();
+    let text = post.to_text();
+    assert_eq!(
+      "---\nformat: 1\nstate: PendingReview\nauthor: alice\nrequired-approvals: 2\napproved-by: bob\n---\nI ate a salad\nfor lunch today",
+      text
+    );
+
+    let mut restored = Post::from_text(&text).unwrap();
+    assert_eq!(PostStatus::PendingReview, restored.status());
+    assert!(restored.history().is_empty());
+    restored.approve(&Actor::reviewer("carol")).unwrap();
+    assert_eq!("I ate a salad\nfor lunch today", restored.content());
+  }
+
+  #[test]
+  fn loading_rejects_unknown_and_inconsistent_states() {
+    let text = "---\nformat: 1\nstate: Archived\nauthor: alice\nrequired-approvals: 1\n---\n";
+    assert_eq!(
+      Err(StorageError::UnknownState("Archived".to_string())),
+      Post::from_text(text).map(|p| p.status())
+    );
+
+    let text =
+      "---\nformat: 1\nstate: Draft\nauthor: alice\nrequired-approvals: 1\napproved-by: bob\n---\n";
+    assert!(matches!(
+      Post::from_text(text),
+      Err(StorageError::Malformed(_))
+    ));
+
+    let text = "---\nformat: 1\nstate: Draft\nrequired-approvals: 1\n---\n";
+    assert!(matches!(
+      Post::from_text(text),
+      Err(StorageError::MissingField("author"))
+    ));
+
+    let text = "---\nformat: 2\nstate: Draft\nauthor: alice\nrequired-approvals: 1\n---\n";
+    assert!(matches!(
+      Post::from_text(text),
+      Err(StorageError::UnsupportedVersion(2))
+    ));
+
+    assert!(matches!(
+      Post::from_json("{\"state\": 1}"),
+      Err(StorageError::Json(_))
+    ));
+  }
+
+  #[test]
+  fn scheduled_post_keeps_its_publication_time() {
+    let mut post = Post::new(&Actor::author("alice"));
+    post
+      .add_text(&Actor::author("alice"), "I ate a salad for lunch today")
+      .unwrap();
+    post.request_review(&Actor::author("alice")).unwrap();
+    post
+      .schedule(&Actor::reviewer("bob"), 1_700_000_000)
+      .unwrap();
+    post.approve(&Actor::reviewer("bob")).unwrap();
+
+    let text = post.to_text();
+    assert!(text.contains("state: Scheduled\n"));
+    assert!(text.contains("publish-at: 1700000000\n"));
+
+    let restored = Post::from_text(&text).unwrap();
+    assert_eq!(post.status(), restored.status());
+    let restored = Post::from_json(&post.to_json()).unwrap();
+    assert_eq!(post.status(), restored.status());
+    assert_eq!(post.history(), restored.history());
+
+    let text =
+      "---\nformat: 1\nstate: Scheduled\nauthor: alice\nrequired-approvals: 1\napproved-by: bob\n---\n";
+    assert!(matches!(
+      Post::from_text(text),
+      Err(StorageError::MissingField("publish-at"))
+    ));
+  }
+
+  #[test]
+  fn loading_doesnt_get_around_the_review() {
+    let load = |state: &str, more: &str| {
+      let text = format!(
+        "---\nformat: 1\nstate: {}\nauthor: alice\nrequired-approvals: 2\n{}---\n",
+        state, more
+      );
+      Post::from_text(&text).map(|p| p.status())
+    };
+    let malformed = |reason: &str| Err(StorageError::Malformed(reason.to_string()));
+
+    // short of the quorum
+    assert_eq!(
+      malformed("a post in Published must have reached its approval quorum"),
+      load("Published", "approved-by: bob\n")
+    );
+    assert_eq!(
+      malformed("a post in Scheduled must have reached its approval quorum"),
+      load("Scheduled", "approved-by: bob\npublish-at: 200\n")
+    );
+    assert_eq!(
+      Ok(PostStatus::Published),
+      load("Published", "approved-by: bob\napproved-by: carol\n")
+    );
+
+    // with a publication time that the state can't have
+    assert_eq!(
+      malformed("a post in Draft can't have `publish-at`"),
+      load("Draft", "publish-at: 200\n")
+    );
+    assert_eq!(
+      malformed("a post in Published can't have `publish-at`"),
+      load(
+        "Published",
+        "approved-by: bob\napproved-by: carol\npublish-at: 200\n"
+      )
+    );
+    let json = Post::with_required_approvals(&Actor::author("alice"), 1)
+      .to_json()
+      .replace("\"publish_at\": null", "\"publish_at\": 200");
+    assert_eq!(
+      malformed("a post in Draft can't have `publish_at`"),
+      Post::from_json(&json).map(|p| p.status())
+    );
+  }
+}
@@ -0,0 +1,220 @@
+// A Blog has to keep posts of all four types side by side, so it stores them as AnyPost. The
+// compiler can't check the transitions of a post whose type is only known at runtime, so here
+// they happen in place, and a post in the wrong state is refused with an error instead.
+use super::{
+  Action, Actor, AnyPost, DraftPost, PostData, PostState, Refused, RolePolicy, Timestamp,
+  TransitionError,
+};
+use blog_common::blog::BlogPost;
+use blog_common::revisions::RevisionStore;
+use std::mem;
+use std::sync::Arc;
+
+impl BlogPost for AnyPost {
+  type Status = PostState;
+
+  fn status(&self) -> PostState {
+    self.state()
+  }
+
+  fn published_content(&self) -> &str {
+    match self {
+      AnyPost::Published(post) => post.content(),
+      AnyPost::Scheduled(post) => post.content(),
+      AnyPost::Draft(_) | AnyPost::PendingReview(_) => "",
+    }
+  }
+}
+
+impl AnyPost {
+  pub fn html(&self) -> Option<String> {
+    match self {
+      AnyPost::Published(post) => Some(post.html()),
+      AnyPost::Scheduled(post) => post.html(),
+      AnyPost::Draft(_) | AnyPost::PendingReview(_) => None,
+    }
+  }
+}
+
+impl<P> Refused<P> {
+  fn map<Q>(self, f: impl FnOnce(P) -> Q) -> Refused<Q> {
+    Refused::new(f(*self.post), self.error)
+  }
+}
+
+fn invalid(post: AnyPost, action: Action) -> Refused<AnyPost> {
+  let state = post.state();
+  Refused::new(post, TransitionError::InvalidTransition { action, state })
+}
+
+// Wraps the typed transition of the post's current state, or refuses the action if there's none.
+macro_rules! any_transition {
+  ($post:expr, $action:expr, $from:ident => $to:ident, |$typed:ident| $call:expr) => {
+    $post.transition(|post| match post {
+      AnyPost::$from($typed) => $call
+        .map(AnyPost::$to)
+        .map_err(|refused| refused.map(AnyPost::$from)),
+      post => Err(invalid(post, $action)),
+    })
+  };
+}
+
+impl AnyPost {
+  pub fn author(&self) -> &str {
+    match self {
+      AnyPost::Draft(post) => post.author(),
+      AnyPost::PendingReview(post) => post.author(),
+      AnyPost::Scheduled(post) => post.author(),
+      AnyPost::Published(post) => post.author(),
+    }
+  }
+
+  // The transitions consume the post, so an empty draft stands in for it in the meantime.
+  fn transition(
+    &mut self,
+    f: impl FnOnce(AnyPost) -> Result<AnyPost, Refused<AnyPost>>,
+  ) -> Result<(), TransitionError> {
+    let placeholder = AnyPost::Draft(DraftPost {
+      data: PostData {
+        content: String::new(),
+        author: String::new(),
+        revisions: RevisionStore::new(),
+        policy: Arc::new(RolePolicy),
+      },
+    });
+
+    match f(mem::replace(self, placeholder)) {
+      Ok(next) => {
+        *self = next;
+        Ok(())
+      }
+      Err(refused) => {
+        let error = refused.error.clone();
+        *self = refused.into_post();
+        Err(error)
+      }
+    }
+  }
+
+  pub fn request_review(&mut self, actor: &Actor) -> Result<(), TransitionError> {
+    any_transition!(self, Action::RequestReview, Draft => PendingReview, |post| post.request_review(actor))
+  }
+
+  pub fn approve(&mut self, reviewer: &Actor) -> Result<(), TransitionError> {
+    any_transition!(self, Action::Approve, PendingReview => Published, |post| post.approve(reviewer))
+  }
+
+  pub fn reject(&mut self, reviewer: &Actor) -> Result<(), TransitionError> {
+    any_transition!(self, Action::Reject, PendingReview => Draft, |post| post.reject(reviewer))
+  }
+
+  pub fn schedule(
+    &mut self,
+    reviewer: &Actor,
+    publish_at: Timestamp,
+  ) -> Result<(), TransitionError> {
+    any_transition!(self, Action::Schedule, PendingReview => Scheduled, |post| post.schedule(reviewer, publish_at))
+  }
+
+  pub fn unschedule(&mut self, actor: &Actor) -> Result<(), TransitionError> {
+    any_transition!(self, Action::Unschedule, Scheduled => PendingReview, |post| post.unschedule(actor))
+  }
+
+  pub fn publish(&mut self, actor: &Actor) -> Result<(), TransitionError> {
+    any_transition!(self, Action::Publish, Scheduled => Published, |post| post.publish(actor))
+  }
+
+  pub fn revise(&mut self, actor: &Actor) -> Result<(), TransitionError> {
+    any_transition!(self, Action::Revise, Published => Draft, |post| post.revise(actor))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Blog, ManualClock, Post};
+
+  fn alice() -> Actor {
+    Actor::author("alice")
+  }
+
+  fn bob() -> Actor {
+    Actor::reviewer("bob")
+  }
+
+  fn draft(text: &str) -> AnyPost {
+    let mut post = Post::new(&alice());
+    post.add_text(&alice(), text).unwrap();
+    post.into()
+  }
+
+  #[test]
+  fn reviewer_approves_all_pending_posts_at_once() {
+    let mut blog = Blog::new();
+    let salad = blog.insert(draft("I ate a salad for lunch today"));
+    let soup = blog.insert(draft("Soup for dinner"));
+    let secret = blog.insert(draft("Secret salad recipe"));
+
+    for id in [salad, soup] {
+      blog.get_mut(id).unwrap().request_review(&alice()).unwrap();
+    }
+    let outcomes = blog.update_with_status(PostState::PendingReview, |post| post.approve(&bob()));
+
+    assert_eq!(vec![(salad, Ok(())), (soup, Ok(()))], outcomes);
+    assert_eq!(vec![salad, soup], blog.with_status(PostState::Published));
+    assert_eq!(vec![secret], blog.with_status(PostState::Draft));
+    assert_eq!(None, blog.get(secret).unwrap().html());
+    assert_eq!(vec![salad], blog.search("salad"));
+  }
+
+  #[test]
+  fn transitions_of_the_wrong_state_are_refused() {
+    let mut post = draft("I ate a salad for lunch today");
+    assert_eq!(
+      Err(TransitionError::InvalidTransition {
+        action: Action::Approve,
+        state: PostState::Draft,
+      }),
+      post.approve(&bob())
+    );
+
+    // a refused transition leaves the post as it was
+    post.request_review(&alice()).unwrap();
+    assert!(matches!(
+      post.approve(&alice()),
+      Err(TransitionError::Permission(_))
+    ));
+    assert_eq!(PostState::PendingReview, post.state());
+    assert_eq!("alice", post.author());
+  }
+
+  #[test]
+  fn scheduled_posts_are_searchable_once_due() {
+    let clock = Arc::new(ManualClock::new(100));
+    let mut post = draft("Salad again");
+    post.request_review(&alice()).unwrap();
+    post.schedule(&bob(), 200).unwrap();
+    if let AnyPost::Scheduled(scheduled) = post {
+      post = AnyPost::Scheduled(scheduled.with_clock(clock.clone()));
+    }
+
+    let mut blog = Blog::new();
+    let id = blog.insert(post);
+    assert!(blog.search("salad").is_empty());
+    assert_eq!(
+      Err(TransitionError::NotDue { publish_at: 200 }),
+      blog.get_mut(id).unwrap().publish(&bob())
+    );
+
+    assert_eq!(None, blog.get(id).unwrap().html());
+
+    clock.set(200);
+    assert_eq!(vec![id], blog.search("salad"));
+    assert_eq!(
+      Some("<p>Salad again</p>\n".to_string()),
+      blog.get(id).unwrap().html()
+    );
+    blog.get_mut(id).unwrap().publish(&bob()).unwrap();
+    assert_eq!(vec![id], blog.with_status(PostState::Published));
+  }
+}
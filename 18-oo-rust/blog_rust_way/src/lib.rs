@@ -64,6 +64,146 @@
 // of the state to the types of the structs. The Post struct will represent a
 // published post, and it has a content method that returns the content.
 
+mod blog;
+mod storage;
+
+use blog_common::access::authorize;
+use blog_common::markdown;
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+pub use blog_common::access::{Action, Actor, PermissionError, Policy, Role, RolePolicy};
+pub use blog_common::blog::{Blog, BlogPost, PostId};
+pub use blog_common::clock::{Clock, ManualClock, SystemClock, Timestamp};
+pub use blog_common::revisions::{Revision, RevisionError, RevisionStore};
+pub use blog_common::storage::StorageError;
+pub use storage::AnyPost;
+
+// Which of the four types a post is, for the posts whose type is only known at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PostState {
+  Draft,
+  PendingReview,
+  Scheduled,
+  Published,
+}
+
+impl PostState {
+  pub fn name(&self) -> &'static str {
+    match self {
+      PostState::Draft => DraftPost::STATE,
+      PostState::PendingReview => PendingReviewPost::STATE,
+      PostState::Scheduled => ScheduledPost::STATE,
+      PostState::Published => Post::STATE,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransitionError {
+  Permission(PermissionError),
+  NotDue { publish_at: Timestamp },
+  Revision(RevisionError),
+  // only posts whose state is known at runtime (AnyPost) can be asked for the wrong transition
+  InvalidTransition { action: Action, state: PostState },
+}
+
+impl fmt::Display for TransitionError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      TransitionError::Permission(e) => e.fmt(f),
+      TransitionError::NotDue { publish_at } => {
+        write!(f, "post isn't due for publication before {}", publish_at)
+      }
+      TransitionError::Revision(e) => e.fmt(f),
+      TransitionError::InvalidTransition { action, state } => {
+        write!(f, "can't {} a post in {}", action.name(), state.name())
+      }
+    }
+  }
+}
+
+impl Error for TransitionError {}
+
+impl From<PermissionError> for TransitionError {
+  fn from(e: PermissionError) -> Self {
+    TransitionError::Permission(e)
+  }
+}
+
+impl From<RevisionError> for TransitionError {
+  fn from(e: RevisionError) -> Self {
+    TransitionError::Revision(e)
+  }
+}
+
+// Transitions consume the post, so a refused transition has to hand it back to the caller.
+// The post is boxed to keep the `Err` variant of the transitions' results small.
+pub struct Refused<P> {
+  pub post: Box<P>,
+  pub error: TransitionError,
+}
+
+impl<P> Refused<P> {
+  fn new(post: P, error: TransitionError) -> Refused<P> {
+    Refused {
+      post: Box::new(post),
+      error,
+    }
+  }
+
+  pub fn into_post(self) -> P {
+    *self.post
+  }
+}
+
+impl<P> fmt::Debug for Refused<P> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.debug_struct("Refused")
+      .field("error", &self.error)
+      .finish()
+  }
+}
+
+impl<P> fmt::Display for Refused<P> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    self.error.fmt(f)
+  }
+}
+
+impl<P> Error for Refused<P> {}
+
+// What every post carries along, whatever state it's in.
+struct PostData {
+  content: String,
+  author: String,
+  revisions: RevisionStore,
+  policy: Arc<dyn Policy>,
+}
+
+trait HasPostData {
+  fn data(&self) -> &PostData;
+  fn data_mut(&mut self) -> &mut PostData;
+}
+
+// Runs a transition past the post's policy and snapshots the content on its way out of the
+// current state, or hands the post back if the actor isn't allowed to do that.
+fn begin_transition<P: HasPostData>(
+  mut post: P,
+  actor: &Actor,
+  action: Action,
+) -> Result<P, Refused<P>> {
+  let data = post.data();
+  if let Err(e) = authorize(data.policy.as_ref(), actor, action, &data.author) {
+    return Err(Refused::new(post, e.into()));
+  }
+
+  let data = post.data_mut();
+  data.revisions.snapshot(action.name(), &data.content);
+  Ok(post)
+}
+
 // Implementing Transitions as Transformations into Different Types:
 // -----------------------------------------------------------------
 // So how do we get a published post? We want to enforce the rule that a draft post has to
@@ -73,48 +213,492 @@
 // PendingReviewPost, and defining an approve method on PendingReviewPost to return a Post.
 
 pub struct Post {
-  content: String,
+  data: PostData,
+}
+
+pub struct ScheduledPost {
+  data: PostData,
+  publish_at: Timestamp,
+  clock: Arc<dyn Clock>,
 }
 
 pub struct PendingReviewPost {
-  content: String,
+  data: PostData,
 }
 
 pub struct DraftPost {
-  content: String,
+  data: PostData,
+}
+
+// The accessors every post type shares.
+macro_rules! impl_post_data {
+  ($($post:ident),*) => {
+    $(
+      impl HasPostData for $post {
+        fn data(&self) -> &PostData {
+          &self.data
+        }
+
+        fn data_mut(&mut self) -> &mut PostData {
+          &mut self.data
+        }
+      }
+
+      impl $post {
+        pub fn author(&self) -> &str {
+          &self.data.author
+        }
+
+        // Content snapshots taken at each state transition, for editorial audits.
+        pub fn revisions(&self) -> &RevisionStore {
+          &self.data.revisions
+        }
+
+        // Replaces the default RolePolicy deciding who may take the post through its transitions.
+        pub fn with_policy(mut self, policy: Arc<dyn Policy>) -> $post {
+          self.data.policy = policy;
+          self
+        }
+      }
+    )*
+  };
 }
 
+impl_post_data!(DraftPost, PendingReviewPost, ScheduledPost, Post);
+
 impl Post {
-  pub fn new() -> DraftPost {
+  pub fn new(author: &Actor) -> DraftPost {
     DraftPost {
-      content: String::new(),
+      data: PostData {
+        content: String::new(),
+        author: author.name.clone(),
+        revisions: RevisionStore::new(),
+        policy: Arc::new(RolePolicy),
+      },
     }
   }
 
   pub fn content(&self) -> &str {
-    &self.content
+    &self.data.content
+  }
+
+  // Like content(), rendering to HTML is only there for the types of posts readers get to see;
+  // a draft or a post pending review can't be rendered because it has no such method.
+  pub fn html(&self) -> String {
+    markdown::render(&self.data.content)
+  }
+
+  // A published post can't be edited in place; we consume it and start a new draft from its
+  // content, keeping the published content as a revision.
+  pub fn revise(self, actor: &Actor) -> Result<DraftPost, Refused<Post>> {
+    let post = begin_transition(self, actor, Action::Revise)?;
+    Ok(DraftPost { data: post.data })
+  }
+
+  // Starts a new draft from the content of an older revision, while this post stays published.
+  pub fn restore(&self, actor: &Actor, number: usize) -> Result<DraftPost, TransitionError> {
+    authorize(
+      self.data.policy.as_ref(),
+      actor,
+      Action::Restore,
+      &self.data.author,
+    )?;
+    let content = self.data.revisions.get(number)?.content.clone();
+    let mut revisions = self.data.revisions.clone();
+    revisions.snapshot(&format!("restore {}", number), &content);
+
+    Ok(DraftPost {
+      data: PostData {
+        content,
+        author: self.data.author.clone(),
+        revisions,
+        policy: Arc::clone(&self.data.policy),
+      },
+    })
   }
 }
 
 impl PendingReviewPost {
   // we want to consume PendingReviewPost and create and return a "published" Post
-  pub fn approve(self) -> Post {
-    Post {
-      content: self.content,
+  pub fn approve(self, reviewer: &Actor) -> Result<Post, Refused<PendingReviewPost>> {
+    let post = begin_transition(self, reviewer, Action::Approve)?;
+    Ok(Post { data: post.data })
+  }
+
+  // Rejection sends the post back to its author as a draft.
+  pub fn reject(self, reviewer: &Actor) -> Result<DraftPost, Refused<PendingReviewPost>> {
+    let post = begin_transition(self, reviewer, Action::Reject)?;
+    Ok(DraftPost { data: post.data })
+  }
+
+  // Approves the post, but it only goes live once the clock reaches `publish_at`.
+  pub fn schedule(
+    self,
+    reviewer: &Actor,
+    publish_at: Timestamp,
+  ) -> Result<ScheduledPost, Refused<PendingReviewPost>> {
+    // scheduling approves the post, so the author mustn't be able to schedule it either, and
+    // both are checked before the single snapshot of the transition
+    let data = self.data();
+    if let Err(e) = authorize(
+      data.policy.as_ref(),
+      reviewer,
+      Action::Approve,
+      &data.author,
+    ) {
+      return Err(Refused::new(self, e.into()));
     }
+    let post = begin_transition(self, reviewer, Action::Schedule)?;
+    Ok(ScheduledPost {
+      data: post.data,
+      publish_at,
+      clock: Arc::new(SystemClock),
+    })
+  }
+}
+
+impl ScheduledPost {
+  // Replaces the system clock, which decides when the post goes live.
+  pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> ScheduledPost {
+    self.clock = clock;
+    self
+  }
+
+  pub fn publish_at(&self) -> Timestamp {
+    self.publish_at
+  }
+
+  pub fn is_due(&self) -> bool {
+    self.clock.now() >= self.publish_at
+  }
+
+  // Unlike the other unpublished posts, a scheduled post has content -- once it's due.
+  pub fn content(&self) -> &str {
+    if self.is_due() {
+      &self.data.content
+    } else {
+      ""
+    }
+  }
+
+  pub fn html(&self) -> Option<String> {
+    if self.is_due() {
+      Some(markdown::render(&self.data.content))
+    } else {
+      None
+    }
+  }
+
+  // Unscheduling sends the post back to review, which has to approve it again.
+  pub fn unschedule(self, actor: &Actor) -> Result<PendingReviewPost, Refused<ScheduledPost>> {
+    let post = begin_transition(self, actor, Action::Unschedule)?;
+    Ok(PendingReviewPost { data: post.data })
+  }
+
+  // Turns the post into a published one once it's due; until then we get the post back.
+  pub fn publish(self, actor: &Actor) -> Result<Post, Refused<ScheduledPost>> {
+    if !self.is_due() {
+      let publish_at = self.publish_at;
+      return Err(Refused::new(self, TransitionError::NotDue { publish_at }));
+    }
+
+    let post = begin_transition(self, actor, Action::Publish)?;
+    Ok(Post { data: post.data })
   }
 }
 
 impl DraftPost {
-  pub fn add_text(&mut self, text: &str) {
+  pub fn add_text(&mut self, actor: &Actor, text: &str) -> Result<(), TransitionError> {
+    authorize(
+      self.data.policy.as_ref(),
+      actor,
+      Action::Edit,
+      &self.data.author,
+    )?;
     // self.content = text.to_string();
-    self.content.push_str(text);
+    self.data.content.push_str(text);
+    Ok(())
   }
 
   // we want to consume DraftPost and create and return a PendingReviewPost
-  pub fn request_review(self) -> PendingReviewPost {
-    PendingReviewPost {
-      content: self.content,
+  pub fn request_review(self, actor: &Actor) -> Result<PendingReviewPost, Refused<DraftPost>> {
+    let post = begin_transition(self, actor, Action::RequestReview)?;
+    Ok(PendingReviewPost { data: post.data })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn alice() -> Actor {
+    Actor::author("alice")
+  }
+
+  fn bob() -> Actor {
+    Actor::reviewer("bob")
+  }
+
+  fn pending_post(text: &str) -> PendingReviewPost {
+    let mut post = Post::new(&alice());
+    post.add_text(&alice(), text).unwrap();
+    post.request_review(&alice()).unwrap()
+  }
+
+  fn published_post(text: &str) -> Post {
+    pending_post(text).approve(&bob()).unwrap()
+  }
+
+  fn contents(revisions: &RevisionStore) -> Vec<&str> {
+    revisions
+      .list()
+      .iter()
+      .map(|r| r.content.as_str())
+      .collect()
+  }
+
+  #[test]
+  fn revise_keeps_published_content_as_revision() {
+    let post = published_post("I ate a salad for lunch today");
+
+    let mut draft = post.revise(&alice()).unwrap();
+    let latest = draft.revisions().latest().unwrap();
+    assert_eq!(
+      ("revise", "I ate a salad for lunch today"),
+      (latest.action.as_str(), latest.content.as_str())
+    );
+
+    draft.add_text(&alice(), " and a soup").unwrap();
+    let post = draft
+      .request_review(&alice())
+      .unwrap()
+      .approve(&bob())
+      .unwrap();
+    assert_eq!("I ate a salad for lunch today and a soup", post.content());
+  }
+
+  #[test]
+  fn revisions_are_taken_at_each_transition() {
+    let mut draft = published_post("v1").revise(&alice()).unwrap();
+    draft.add_text(&alice(), ", v2").unwrap();
+    let post = draft
+      .request_review(&alice())
+      .unwrap()
+      .approve(&bob())
+      .unwrap();
+
+    let actions: Vec<&str> = post
+      .revisions()
+      .list()
+      .iter()
+      .map(|r| r.action.as_str())
+      .collect();
+    assert_eq!(
+      vec![
+        "request_review",
+        "approve",
+        "revise",
+        "request_review",
+        "approve"
+      ],
+      actions
+    );
+    assert_eq!(
+      vec!["v1", "v1", "v1", "v1, v2", "v1, v2"],
+      contents(post.revisions())
+    );
+
+    assert!(post
+      .revisions()
+      .diff(2, 5)
+      .unwrap()
+      .ends_with("@@ -1 +1 @@\n-v1\n+v1, v2\n"));
+  }
+
+  #[test]
+  fn restore_starts_a_new_draft_from_an_older_revision() {
+    let mut draft = published_post("v1").revise(&alice()).unwrap();
+    draft.add_text(&alice(), ", v2").unwrap();
+    let post = draft
+      .request_review(&alice())
+      .unwrap()
+      .approve(&bob())
+      .unwrap();
+
+    let draft = post.restore(&alice(), 2).unwrap();
+    assert_eq!("v1", draft.revisions().latest().unwrap().content);
+    assert_eq!("restore 2", draft.revisions().latest().unwrap().action);
+    assert_eq!("v1, v2", post.content());
+
+    let restored = draft
+      .request_review(&alice())
+      .unwrap()
+      .approve(&bob())
+      .unwrap();
+    assert_eq!("v1", restored.content());
+
+    assert!(matches!(
+      post.restore(&alice(), 42),
+      Err(TransitionError::Revision(RevisionError::NotFound(42)))
+    ));
+  }
+
+  #[test]
+  fn scheduled_post_goes_live_when_the_clock_says_so() {
+    let clock = Arc::new(ManualClock::new(1_000));
+    let post = pending_post("I ate a salad for lunch today")
+      .schedule(&bob(), 1_100)
+      .unwrap()
+      .with_clock(clock.clone());
+    assert_eq!("", post.content());
+
+    clock.advance(99);
+    let refused = post.publish(&bob()).err().unwrap();
+    assert_eq!(TransitionError::NotDue { publish_at: 1_100 }, refused.error);
+    let post = refused.into_post();
+    assert_eq!("", post.content());
+
+    clock.advance(1);
+    assert_eq!("I ate a salad for lunch today", post.content());
+    let post = post.publish(&bob()).unwrap();
+    assert_eq!("I ate a salad for lunch today", post.content());
+    assert_eq!("publish", post.revisions().latest().unwrap().action);
+  }
+
+  #[test]
+  fn unscheduling_sends_post_back_to_review() {
+    let post = pending_post("I ate a salad for lunch today")
+      .schedule(&bob(), 1_100)
+      .unwrap()
+      .unschedule(&bob())
+      .unwrap();
+
+    // ## Compiler Error: method not available on the PendingReviewPost
+    // post.content();
+
+    let post = post.approve(&bob()).unwrap();
+    assert_eq!("I ate a salad for lunch today", post.content());
+  }
+
+  #[test]
+  fn reject_sends_post_back_to_draft() {
+    let mut draft = pending_post("I ate a salad").reject(&bob()).unwrap();
+    draft.add_text(&alice(), " for lunch today").unwrap();
+
+    let post = draft
+      .request_review(&alice())
+      .unwrap()
+      .approve(&bob())
+      .unwrap();
+    assert_eq!("I ate a salad for lunch today", post.content());
+  }
+
+  #[test]
+  fn only_the_author_edits_a_draft() {
+    let mut draft = pending_post("I ate a salad").reject(&bob()).unwrap();
+    assert_eq!(
+      Err(TransitionError::Permission(PermissionError::Forbidden {
+        actor: "bob".to_string(),
+        action: Action::Edit,
+      })),
+      draft.add_text(&bob(), " and a steak")
+    );
+    assert!(draft
+      .add_text(&Actor::author("dave"), " and a steak")
+      .is_err());
+
+    let post = draft
+      .request_review(&alice())
+      .unwrap()
+      .approve(&bob())
+      .unwrap();
+    assert_eq!("I ate a salad", post.content());
+  }
+
+  #[test]
+  fn refused_transitions_hand_the_post_back() {
+    let post = pending_post("I ate a salad for lunch today");
+
+    let refused = post.approve(&alice()).err().unwrap();
+    assert_eq!(
+      TransitionError::Permission(PermissionError::SelfApproval {
+        actor: "alice".to_string()
+      }),
+      refused.error
+    );
+
+    let refused = refused
+      .into_post()
+      .approve(&Actor::author("carol"))
+      .err()
+      .unwrap();
+    assert!(matches!(
+      refused.error,
+      TransitionError::Permission(PermissionError::Forbidden {
+        action: Action::Approve,
+        ..
+      })
+    ));
+
+    // the refused post is still pending review, and can still be approved
+    let post = refused.into_post().approve(&bob()).unwrap();
+    assert_eq!("I ate a salad for lunch today", post.content());
+  }
+
+  #[test]
+  fn authors_cant_schedule_their_own_posts() {
+    struct AnythingGoes;
+
+    impl Policy for AnythingGoes {
+      fn permits(&self, _actor: &Actor, _action: Action, _author: &str) -> bool {
+        true
+      }
     }
+
+    let post = pending_post("I ate a salad for lunch today").with_policy(Arc::new(AnythingGoes));
+    let refused = post.schedule(&alice(), 1_100).err().unwrap();
+    assert!(matches!(
+      refused.error,
+      TransitionError::Permission(PermissionError::SelfApproval { .. })
+    ));
+  }
+
+  #[test]
+  fn refused_schedules_leave_no_revision_behind() {
+    struct NoScheduling;
+
+    impl Policy for NoScheduling {
+      fn permits(&self, _actor: &Actor, action: Action, _author: &str) -> bool {
+        action != Action::Schedule
+      }
+    }
+
+    let post = pending_post("I ate a salad for lunch today").with_policy(Arc::new(NoScheduling));
+    let before = post.revisions().list().len();
+    let refused = post.schedule(&bob(), 1_100).err().unwrap();
+    assert!(matches!(
+      refused.error,
+      TransitionError::Permission(PermissionError::Forbidden {
+        action: Action::Schedule,
+        ..
+      })
+    ));
+    assert_eq!(before, refused.into_post().revisions().list().len());
+
+    // a schedule that goes through takes one snapshot, not one for approving too
+    let post = pending_post("I ate a salad for lunch today");
+    let before = post.revisions().list().len();
+    let post = post.schedule(&bob(), 1_100).unwrap();
+    assert_eq!(before + 1, post.revisions().list().len());
+    assert_eq!("schedule", post.revisions().latest().unwrap().action);
+  }
+
+  #[test]
+  fn published_post_renders_to_escaped_html() {
+    let post = published_post("Salad & soup:\n\n- *salad*\n- <soup>");
+    assert_eq!(
+      "<p>Salad &amp; soup:</p>\n<ul>\n<li><em>salad</em></li>\n<li>&lt;soup&gt;</li>\n</ul>\n",
+      post.html()
+    );
   }
 }
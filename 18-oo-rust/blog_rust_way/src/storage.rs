@@ -0,0 +1,376 @@
+// Each typestate type is stored together with the name of its state, so that loading a stored
+// post into the wrong type fails with a typed error instead of silently changing the post's state.
+// AnyPost is there for callers who don't know in advance which state a stored post is in.
+use super::{DraftPost, PendingReviewPost, Post, PostData, PostState, ScheduledPost};
+use blog_common::access::RolePolicy;
+use blog_common::clock::{SystemClock, Timestamp};
+use blog_common::revisions::RevisionStore;
+use blog_common::storage::{check_version, FrontMatter, StorageError, FORMAT_VERSION};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const STATES: [&str; 4] = ["Draft", "PendingReview", "Scheduled", "Published"];
+
+#[derive(Serialize, Deserialize)]
+struct PostRecord {
+  version: u32,
+  state: String,
+  author: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  publish_at: Option<Timestamp>,
+  content: String,
+  #[serde(default)]
+  revisions: RevisionStore,
+}
+
+impl PostRecord {
+  fn new(state: &str, data: &PostData) -> PostRecord {
+    PostRecord {
+      version: FORMAT_VERSION,
+      state: state.to_string(),
+      author: data.author.clone(),
+      publish_at: None,
+      content: data.content.clone(),
+      revisions: data.revisions.clone(),
+    }
+  }
+
+  // A loaded post gets the default policy, see with_policy() to replace it.
+  fn into_data(self) -> PostData {
+    PostData {
+      content: self.content,
+      author: self.author,
+      revisions: self.revisions,
+      policy: Arc::new(RolePolicy),
+    }
+  }
+
+  fn from_json(json: &str) -> Result<PostRecord, StorageError> {
+    let record: PostRecord = serde_json::from_str(json)?;
+    check_version(record.version)?;
+    Ok(record)
+  }
+
+  // The text format only keeps the state and the content, so a restored post has no revisions.
+  fn from_text(text: &str) -> Result<PostRecord, StorageError> {
+    let (front_matter, body) = FrontMatter::parse(text)?;
+    check_version(front_matter.require_number("format")?)?;
+
+    let publish_at = match front_matter.get("publish-at") {
+      Some(_) => Some(front_matter.require_number("publish-at")?),
+      None => None,
+    };
+    Ok(PostRecord {
+      version: FORMAT_VERSION,
+      state: front_matter.require("state")?.to_string(),
+      author: front_matter.require("author")?.to_string(),
+      publish_at,
+      content: body.to_string(),
+      revisions: RevisionStore::new(),
+    })
+  }
+
+  fn to_json(&self) -> String {
+    // serializing plain strings and numbers can't fail
+    serde_json::to_string_pretty(self).unwrap()
+  }
+
+  fn to_text(&self) -> String {
+    let mut front_matter = FrontMatter::new();
+    front_matter.push("format", self.version);
+    front_matter.push("state", &self.state);
+    front_matter.push("author", &self.author);
+    if let Some(publish_at) = self.publish_at {
+      front_matter.push("publish-at", publish_at);
+    }
+    front_matter.write(&self.content)
+  }
+
+  fn expect_state(&self, expected: &'static str) -> Result<(), StorageError> {
+    if self.state == expected {
+      Ok(())
+    } else if STATES.contains(&self.state.as_str()) {
+      Err(StorageError::StateMismatch {
+        expected,
+        found: self.state.clone(),
+      })
+    } else {
+      Err(StorageError::UnknownState(self.state.clone()))
+    }
+  }
+}
+
+impl DraftPost {
+  fn to_record(&self) -> PostRecord {
+    PostRecord::new(Self::STATE, &self.data)
+  }
+
+  fn from_record(record: PostRecord) -> Result<DraftPost, StorageError> {
+    record.expect_state(Self::STATE)?;
+    Ok(DraftPost {
+      data: record.into_data(),
+    })
+  }
+}
+
+impl PendingReviewPost {
+  fn to_record(&self) -> PostRecord {
+    PostRecord::new(Self::STATE, &self.data)
+  }
+
+  fn from_record(record: PostRecord) -> Result<PendingReviewPost, StorageError> {
+    record.expect_state(Self::STATE)?;
+    Ok(PendingReviewPost {
+      data: record.into_data(),
+    })
+  }
+}
+
+impl ScheduledPost {
+  fn to_record(&self) -> PostRecord {
+    PostRecord {
+      publish_at: Some(self.publish_at),
+      ..PostRecord::new(Self::STATE, &self.data)
+    }
+  }
+
+  // A loaded post uses the system clock, see with_clock() to replace it.
+  fn from_record(record: PostRecord) -> Result<ScheduledPost, StorageError> {
+    record.expect_state(Self::STATE)?;
+    Ok(ScheduledPost {
+      publish_at: record
+        .publish_at
+        .ok_or(StorageError::MissingField("publish_at"))?,
+      data: record.into_data(),
+      clock: Arc::new(SystemClock),
+    })
+  }
+}
+
+impl Post {
+  fn to_record(&self) -> PostRecord {
+    PostRecord::new(Self::STATE, &self.data)
+  }
+
+  fn from_record(record: PostRecord) -> Result<Post, StorageError> {
+    record.expect_state(Self::STATE)?;
+    Ok(Post {
+      data: record.into_data(),
+    })
+  }
+}
+
+// The same public (de)serialization methods for each of the typestate types.
+macro_rules! impl_storage {
+  ($post:ident, $state:literal) => {
+    impl $post {
+      pub const STATE: &'static str = $state;
+
+      pub fn to_json(&self) -> String {
+        self.to_record().to_json()
+      }
+
+      pub fn from_json(json: &str) -> Result<$post, StorageError> {
+        Self::from_record(PostRecord::from_json(json)?)
+      }
+
+      pub fn to_text(&self) -> String {
+        self.to_record().to_text()
+      }
+
+      pub fn from_text(text: &str) -> Result<$post, StorageError> {
+        Self::from_record(PostRecord::from_text(text)?)
+      }
+    }
+  };
+}
+
+impl_storage!(DraftPost, "Draft");
+impl_storage!(PendingReviewPost, "PendingReview");
+impl_storage!(ScheduledPost, "Scheduled");
+impl_storage!(Post, "Published");
+
+// A stored post in whichever state it was saved.
+pub enum AnyPost {
+  Draft(DraftPost),
+  PendingReview(PendingReviewPost),
+  Scheduled(ScheduledPost),
+  Published(Post),
+}
+
+impl AnyPost {
+  pub fn state(&self) -> PostState {
+    match self {
+      AnyPost::Draft(_) => PostState::Draft,
+      AnyPost::PendingReview(_) => PostState::PendingReview,
+      AnyPost::Scheduled(_) => PostState::Scheduled,
+      AnyPost::Published(_) => PostState::Published,
+    }
+  }
+
+  fn from_record(record: PostRecord) -> Result<AnyPost, StorageError> {
+    match record.state.as_str() {
+      DraftPost::STATE => DraftPost::from_record(record).map(AnyPost::Draft),
+      PendingReviewPost::STATE => {
+        PendingReviewPost::from_record(record).map(AnyPost::PendingReview)
+      }
+      ScheduledPost::STATE => ScheduledPost::from_record(record).map(AnyPost::Scheduled),
+      Post::STATE => Post::from_record(record).map(AnyPost::Published),
+      _ => Err(StorageError::UnknownState(record.state)),
+    }
+  }
+
+  fn to_record(&self) -> PostRecord {
+    match self {
+      AnyPost::Draft(post) => post.to_record(),
+      AnyPost::PendingReview(post) => post.to_record(),
+      AnyPost::Scheduled(post) => post.to_record(),
+      AnyPost::Published(post) => post.to_record(),
+    }
+  }
+
+  pub fn to_json(&self) -> String {
+    self.to_record().to_json()
+  }
+
+  pub fn from_json(json: &str) -> Result<AnyPost, StorageError> {
+    Self::from_record(PostRecord::from_json(json)?)
+  }
+
+  pub fn to_text(&self) -> String {
+    self.to_record().to_text()
+  }
+
+  pub fn from_text(text: &str) -> Result<AnyPost, StorageError> {
+    Self::from_record(PostRecord::from_text(text)?)
+  }
+}
+
+impl From<DraftPost> for AnyPost {
+  fn from(post: DraftPost) -> Self {
+    AnyPost::Draft(post)
+  }
+}
+
+impl From<PendingReviewPost> for AnyPost {
+  fn from(post: PendingReviewPost) -> Self {
+    AnyPost::PendingReview(post)
+  }
+}
+
+impl From<ScheduledPost> for AnyPost {
+  fn from(post: ScheduledPost) -> Self {
+    AnyPost::Scheduled(post)
+  }
+}
+
+impl From<Post> for AnyPost {
+  fn from(post: Post) -> Self {
+    AnyPost::Published(post)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Actor;
+
+  fn bob() -> Actor {
+    Actor::reviewer("bob")
+  }
+
+  fn pending_post() -> PendingReviewPost {
+    let alice = Actor::author("alice");
+    let mut post = Post::new(&alice);
+    post
+      .add_text(&alice, "I ate a salad\nfor lunch today")
+      .unwrap();
+    post.request_review(&alice).unwrap()
+  }
+
+  #[test]
+  fn json_round_trip_keeps_revisions() {
+    let post = pending_post();
+    let restored = PendingReviewPost::from_json(&post.to_json()).unwrap();
+    assert_eq!(post.revisions(), restored.revisions());
+
+    assert_eq!("alice", restored.author());
+    let published = restored.approve(&bob()).unwrap();
+    assert_eq!("I ate a salad\nfor lunch today", published.content());
+  }
+
+  #[test]
+  fn text_format_has_front_matter_and_body() {
+    let text = pending_post().approve(&bob()).unwrap().to_text();
+    assert_eq!(
+      "---\nformat: 1\nstate: Published\nauthor: alice\n---\nI ate a salad\nfor lunch today",
+      text
+    );
+    assert_eq!(
+      "I ate a salad\nfor lunch today",
+      Post::from_text(&text).unwrap().content()
+    );
+  }
+
+  #[test]
+  fn loading_into_the_wrong_typestate_fails() {
+    let json = pending_post().to_json();
+
+    assert_eq!(
+      Some(StorageError::StateMismatch {
+        expected: "Published",
+        found: "PendingReview".to_string(),
+      }),
+      Post::from_json(&json).err()
+    );
+    assert!(matches!(
+      DraftPost::from_text("---\nformat: 1\nstate: Archived\nauthor: alice\n---\n"),
+      Err(StorageError::UnknownState(_))
+    ));
+  }
+
+  #[test]
+  fn any_post_loads_the_stored_typestate() {
+    let text = AnyPost::from(pending_post()).to_text();
+
+    match AnyPost::from_text(&text).unwrap() {
+      AnyPost::PendingReview(post) => {
+        assert_eq!(
+          "I ate a salad\nfor lunch today",
+          post.approve(&bob()).unwrap().content()
+        )
+      }
+      other => panic!(
+        "expected a post pending review, got a {} one",
+        other.state().name()
+      ),
+    }
+  }
+
+  #[test]
+  fn scheduled_post_keeps_its_publication_time() {
+    let post = pending_post().schedule(&bob(), 1_700_000_000).unwrap();
+
+    let text = post.to_text();
+    assert_eq!(
+      "---\nformat: 1\nstate: Scheduled\nauthor: alice\npublish-at: 1700000000\n---\nI ate a salad\nfor lunch today",
+      text
+    );
+    assert_eq!(
+      1_700_000_000,
+      ScheduledPost::from_text(&text).unwrap().publish_at()
+    );
+    assert_eq!(
+      1_700_000_000,
+      ScheduledPost::from_json(&post.to_json())
+        .unwrap()
+        .publish_at()
+    );
+
+    let text = "---\nformat: 1\nstate: Scheduled\nauthor: alice\n---\n";
+    assert!(matches!(
+      AnyPost::from_text(text),
+      Err(StorageError::MissingField("publish_at"))
+    ));
+  }
+}
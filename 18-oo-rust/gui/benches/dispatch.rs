@@ -0,0 +1,42 @@
+// Drawing the same buttons through the three kinds of screens: Screen calls each component
+// through a vtable, HomogeneousScreen and TupleScreen know the types at compile time. Tuples
+// only go up to 12 components, so that's what all three get first; the second group compares
+// the two that can hold 1,000. Run with `cargo bench -p gui --bench dispatch`.
+use criterion::{criterion_group, criterion_main, Criterion};
+use gui::{Button, Draw, HomogeneousScreen, Screen, TupleScreen};
+
+fn buttons(n: usize) -> Vec<Button> {
+  (0..n)
+    .map(|i| Button::new(20, 3, &format!("Button {}", i)))
+    .collect()
+}
+
+fn boxed(buttons: Vec<Button>) -> Vec<Box<dyn Draw>> {
+  buttons
+    .into_iter()
+    .map(|b| Box::new(b) as Box<dyn Draw>)
+    .collect()
+}
+
+fn dispatch(c: &mut Criterion) {
+  let mut group = c.benchmark_group("draw 12 components");
+  let dynamic = Screen::new(boxed(buttons(12)));
+  group.bench_function("trait objects", |b| b.iter(|| dynamic.draw()));
+  let homogeneous = HomogeneousScreen::new(buttons(12));
+  group.bench_function("homogeneous", |b| b.iter(|| homogeneous.draw()));
+  let [b0, b1, b2, b3, b4, b5, b6, b7, b8, b9, b10, b11]: [Button; 12] =
+    buttons(12).try_into().ok().unwrap();
+  let tuple = TupleScreen::new((b0, b1, b2, b3, b4, b5, b6, b7, b8, b9, b10, b11));
+  group.bench_function("tuple", |b| b.iter(|| tuple.draw()));
+  group.finish();
+
+  let mut group = c.benchmark_group("draw 1000 components");
+  let dynamic = Screen::new(boxed(buttons(1000)));
+  group.bench_function("trait objects", |b| b.iter(|| dynamic.draw()));
+  let homogeneous = HomogeneousScreen::new(buttons(1000));
+  group.bench_function("homogeneous", |b| b.iter(|| homogeneous.draw()));
+  group.finish();
+}
+
+criterion_group!(benches, dispatch);
+criterion_main!(benches);
@@ -0,0 +1,39 @@
+// Full vs incremental redraw of a screen with 1,000 buttons, of which only the two whose focus
+// changes need to be drawn again. Run with `cargo bench -p gui`.
+use criterion::{criterion_group, criterion_main, Criterion};
+use gui::{Button, Event, Screen};
+
+const COMPONENTS: usize = 1000;
+
+fn screen() -> Screen {
+  let components = (0..COMPONENTS)
+    .map(|i| Box::new(Button::new(20, 3, &format!("Button {}", i))) as Box<_>)
+    .collect();
+  Screen::new(components)
+}
+
+fn redraw(c: &mut Criterion) {
+  let mut group = c.benchmark_group("redraw 1000 components");
+
+  let mut full = screen();
+  group.bench_function("full", |b| {
+    b.iter(|| {
+      full.handle_event(Event::FocusNext);
+      full.draw()
+    })
+  });
+
+  let mut incremental = screen();
+  incremental.render();
+  group.bench_function("incremental", |b| {
+    b.iter(|| {
+      incremental.handle_event(Event::FocusNext);
+      incremental.render()
+    })
+  });
+
+  group.finish();
+}
+
+criterion_group!(benches, redraw);
+criterion_main!(benches);
@@ -0,0 +1,255 @@
+// What assistive technology, like a screen reader, needs to know about the components: what
+// they are (their role), what they're called, what value they hold and what state they're in.
+// The screen gathers that into a tree shaped like its layout, which serializes to JSON for
+// whatever wants to read it, and which can be checked for components nobody could tell apart.
+use crate::Rect;
+use serde::Serialize;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+  Screen,
+  Group,
+  Button,
+  Label,
+  Checkbox,
+  TextInput,
+  ListBox,
+  // components that don't implement Accessible
+  Unknown,
+}
+
+impl Role {
+  // Groups are there for the layout, it's what's inside them that needs a name.
+  pub fn needs_name(&self) -> bool {
+    !matches!(self, Role::Screen | Role::Group)
+  }
+}
+
+impl fmt::Display for Role {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let name = match self {
+      Role::Screen => "screen",
+      Role::Group => "group",
+      Role::Button => "button",
+      Role::Label => "label",
+      Role::Checkbox => "checkbox",
+      Role::TextInput => "text input",
+      Role::ListBox => "list box",
+      Role::Unknown => "unknown component",
+    };
+    f.write_str(name)
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct AccessibleState {
+  #[serde(skip_serializing_if = "std::ops::Not::not")]
+  pub focused: bool,
+  #[serde(skip_serializing_if = "std::ops::Not::not")]
+  pub disabled: bool,
+  // only for components that can be checked at all
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub checked: Option<bool>,
+}
+
+pub trait Accessible {
+  fn role(&self) -> Role;
+
+  // What the component is called, as in a button's label.
+  fn name(&self) -> Option<&str> {
+    None
+  }
+
+  // What the component holds, as in the text of an input or the selected option of a list.
+  fn value(&self) -> Option<String> {
+    None
+  }
+
+  fn state(&self) -> AccessibleState {
+    AccessibleState::default()
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AccessibleNode {
+  pub role: Role,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub name: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub value: Option<String>,
+  pub state: AccessibleState,
+  // where the component is on the screen
+  pub rect: Rect,
+  // the child indices from the screen down, as used by Screen::component_mut()
+  pub path: Vec<usize>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub children: Vec<AccessibleNode>,
+}
+
+impl AccessibleNode {
+  pub fn to_json(&self) -> String {
+    serde_json::to_string_pretty(self).unwrap()
+  }
+
+  // The problems of this node and all of its descendants, depth first.
+  pub fn lint(&self) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    self.collect_issues(&mut issues);
+    issues
+  }
+
+  fn collect_issues(&self, issues: &mut Vec<LintIssue>) {
+    let problem = if self.role == Role::Unknown {
+      Some(Problem::NotAccessible)
+    } else if self.role.needs_name() && self.name.as_deref().is_none_or(|n| n.trim().is_empty()) {
+      Some(Problem::MissingName)
+    } else {
+      None
+    };
+    if let Some(problem) = problem {
+      issues.push(LintIssue {
+        path: self.path.clone(),
+        role: self.role,
+        problem,
+      });
+    }
+    for child in self.children.iter() {
+      child.collect_issues(issues);
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Problem {
+  MissingName,
+  NotAccessible,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintIssue {
+  pub path: Vec<usize>,
+  pub role: Role,
+  pub problem: Problem,
+}
+
+// Issues point at their component the way screen descriptions do, as in
+// `components[1].children[0]`.
+impl fmt::Display for LintIssue {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for (depth, i) in self.path.iter().enumerate() {
+      match depth {
+        0 => write!(f, "components[{}]", i)?,
+        _ => write!(f, ".children[{}]", i)?,
+      }
+    }
+    match self.problem {
+      Problem::MissingName => write!(f, ": {} without an accessible name", self.role),
+      Problem::NotAccessible => write!(f, ": component doesn't implement Accessible"),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Button, Canvas, Checkbox, Draw, Event, Label, Row, Screen, Size, TextInput};
+
+  // A component that doesn't implement Accessible.
+  struct Spacer;
+
+  impl Draw for Spacer {
+    fn size(&self) -> Size {
+      Size::new(2, 1)
+    }
+
+    fn draw(&self, _canvas: &mut Canvas) {}
+  }
+
+  fn form() -> Screen {
+    let mut input = TextInput::new(8, 3).with_accessible_name("Name");
+    input.set_value("Ann");
+    let mut subscribe = Checkbox::new(8, 1, "News");
+    subscribe.checked = true;
+    Screen::new(vec![
+      Box::new(Label::new(5, 1, "Name:")),
+      Box::new(input),
+      Box::new(Row::new().child(subscribe).child(Button::new(4, 3, "OK"))),
+    ])
+  }
+
+  #[test]
+  fn tree_follows_the_layout() {
+    let mut screen = form();
+    screen.handle_event(Event::FocusNext);
+    let tree = screen.accessibility_tree();
+
+    assert_eq!(Role::Screen, tree.role);
+    assert_eq!(Rect::new(0, 0, 12, 7), tree.rect);
+    let input = &tree.children[1];
+    assert_eq!(
+      (Role::TextInput, Some("Name"), Some("Ann")),
+      (input.role, input.name.as_deref(), input.value.as_deref())
+    );
+    assert!(input.state.focused);
+
+    let row = &tree.children[2];
+    assert_eq!(Role::Group, row.role);
+    let button = &row.children[1];
+    assert_eq!(vec![2, 1], button.path);
+    assert_eq!(Rect::new(8, 4, 4, 3), button.rect);
+    assert_eq!(Some(true), row.children[0].state.checked);
+    assert!(tree.lint().is_empty());
+  }
+
+  #[test]
+  fn tree_serializes_to_json() {
+    let screen = Screen::new(vec![Box::new(Checkbox::new(6, 1, "Tea"))]);
+    let json: serde_json::Value =
+      serde_json::from_str(&screen.accessibility_tree().to_json()).unwrap();
+    assert_eq!(
+      serde_json::json!({
+        "role": "screen",
+        "state": {},
+        "rect": { "x": 0, "y": 0, "width": 6, "height": 1 },
+        "path": [],
+        "children": [{
+          "role": "checkbox",
+          "name": "Tea",
+          "state": { "checked": false },
+          "rect": { "x": 0, "y": 0, "width": 6, "height": 1 },
+          "path": [0],
+        }],
+      }),
+      json
+    );
+  }
+
+  #[test]
+  fn lint_flags_components_without_names() {
+    let screen = Screen::new(vec![
+      Box::new(TextInput::new(8, 3)),
+      Box::new(
+        Row::new()
+          .child(Label::new(4, 1, " "))
+          .child(Spacer)
+          .child(Button::new(4, 3, "").with_accessible_name("Close")),
+      ),
+    ]);
+    let issues: Vec<String> = screen
+      .accessibility_tree()
+      .lint()
+      .iter()
+      .map(|issue| issue.to_string())
+      .collect();
+    assert_eq!(
+      vec![
+        "components[0]: text input without an accessible name",
+        "components[1].children[0]: label without an accessible name",
+        "components[1].children[1]: component doesn't implement Accessible",
+      ],
+      issues
+    );
+  }
+}
@@ -0,0 +1,465 @@
+use serde::Serialize;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Size {
+  pub width: u16,
+  pub height: u16,
+}
+
+impl Size {
+  pub fn new(width: u16, height: u16) -> Size {
+    Size { width, height }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct Rect {
+  pub x: u16,
+  pub y: u16,
+  pub width: u16,
+  pub height: u16,
+}
+
+impl Rect {
+  pub fn new(x: u16, y: u16, width: u16, height: u16) -> Rect {
+    Rect {
+      x,
+      y,
+      width,
+      height,
+    }
+  }
+
+  pub fn size(&self) -> Size {
+    Size::new(self.width, self.height)
+  }
+
+  pub fn right(&self) -> u16 {
+    self.x.saturating_add(self.width)
+  }
+
+  pub fn bottom(&self) -> u16 {
+    self.y.saturating_add(self.height)
+  }
+
+  pub fn contains(&self, x: u16, y: u16) -> bool {
+    x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+  }
+
+  // The part of both rectangles, which is empty (0x0) if they don't overlap.
+  pub fn intersect(&self, other: &Rect) -> Rect {
+    let x = self.x.max(other.x);
+    let y = self.y.max(other.y);
+    let right = self.right().min(other.right());
+    let bottom = self.bottom().min(other.bottom());
+    if right <= x || bottom <= y {
+      return Rect::default();
+    }
+    Rect::new(x, y, right - x, bottom - y)
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Border {
+  None,
+  #[default]
+  Single,
+  Double,
+}
+
+impl Border {
+  // The horizontal and vertical lines, then the top-left, top-right, bottom-left and
+  // bottom-right corners.
+  fn chars(&self) -> Option<[char; 6]> {
+    match self {
+      Border::None => None,
+      Border::Single => Some(['─', '│', '┌', '┐', '└', '┘']),
+      Border::Double => Some(['═', '║', '╔', '╗', '╚', '╝']),
+    }
+  }
+
+  // How many cells the border takes on each side.
+  pub fn width(&self) -> u16 {
+    match self {
+      Border::None => 0,
+      _ => 1,
+    }
+  }
+}
+
+// The colors a cell can have. Default leaves it to the terminal, which is what cells are until
+// something is drawn with a color of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Color {
+  #[default]
+  Default,
+  Black,
+  Red,
+  Green,
+  Yellow,
+  Blue,
+  Magenta,
+  Cyan,
+  White,
+}
+
+// Where text goes in a row that's wider than the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Align {
+  #[default]
+  Left,
+  Center,
+  Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cell {
+  c: char,
+  fg: Color,
+  bg: Color,
+}
+
+const BLANK: Cell = Cell {
+  c: ' ',
+  fg: Color::Default,
+  bg: Color::Default,
+};
+
+// A grid of character cells the components draw themselves on. Components don't know where
+// they are on the screen: they draw into the canvas' current area, with (0, 0) at its top-left
+// corner, and whatever falls outside the area is clipped.
+#[derive(Clone)]
+pub struct Canvas {
+  width: u16,
+  height: u16,
+  cells: Vec<Cell>,
+  area: Rect,
+  // Nothing outside of it gets drawn, whatever the area; used to redraw only part of a frame.
+  clip: Rect,
+  // The foreground and background colors of what's being drawn.
+  pen: (Color, Color),
+}
+
+// A cell that differs between two frames, with its new content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellUpdate {
+  pub x: u16,
+  pub y: u16,
+  pub c: char,
+  pub fg: Color,
+  pub bg: Color,
+}
+
+impl Canvas {
+  pub fn new(width: u16, height: u16) -> Canvas {
+    Canvas {
+      width,
+      height,
+      cells: vec![BLANK; width as usize * height as usize],
+      area: Rect::new(0, 0, width, height),
+      clip: Rect::new(0, 0, width, height),
+      pen: (Color::Default, Color::Default),
+    }
+  }
+
+  pub fn width(&self) -> u16 {
+    self.width
+  }
+
+  pub fn height(&self) -> u16 {
+    self.height
+  }
+
+  // The area being drawn on, in canvas coordinates.
+  pub fn area(&self) -> Rect {
+    self.area
+  }
+
+  // Runs `f` with the area narrowed down to `rect`, which is relative to the current area.
+  pub fn within<R>(&mut self, rect: Rect, f: impl FnOnce(&mut Canvas) -> R) -> R {
+    let outer = self.area;
+    let inner = Rect::new(
+      outer.x.saturating_add(rect.x),
+      outer.y.saturating_add(rect.y),
+      rect.width,
+      rect.height,
+    );
+    // an area that's clipped away entirely keeps its origin, so that nothing gets drawn at (0, 0)
+    self.area = match inner.intersect(&outer) {
+      clipped if clipped.width == 0 => Rect::new(inner.x, inner.y, 0, 0),
+      clipped => Rect::new(
+        inner.x,
+        inner.y,
+        clipped.right() - inner.x,
+        clipped.bottom() - inner.y,
+      ),
+    };
+    let result = f(self);
+    self.area = outer;
+    result
+  }
+
+  // Runs `f` with drawing restricted to `rect`, which is in canvas coordinates.
+  pub fn clipped<R>(&mut self, rect: Rect, f: impl FnOnce(&mut Canvas) -> R) -> R {
+    let outer = self.clip;
+    self.clip = outer.intersect(&rect);
+    let result = f(self);
+    self.clip = outer;
+    result
+  }
+
+  // Runs `f` with everything drawn in the given colors.
+  pub fn painted<R>(&mut self, fg: Color, bg: Color, f: impl FnOnce(&mut Canvas) -> R) -> R {
+    let outer = self.pen;
+    self.pen = (fg, bg);
+    let result = f(self);
+    self.pen = outer;
+    result
+  }
+
+  // Whether anything drawn in `rect` (relative to the current area) could show up at all.
+  pub fn is_visible(&self, rect: Rect) -> bool {
+    let rect = Rect::new(
+      self.area.x.saturating_add(rect.x),
+      self.area.y.saturating_add(rect.y),
+      rect.width,
+      rect.height,
+    );
+    let visible = rect.intersect(&self.clip);
+    visible.width > 0 && visible.height > 0
+  }
+
+  // The cells that changed since `previous`; all of them if the sizes differ.
+  pub fn diff(&self, previous: &Canvas) -> Vec<CellUpdate> {
+    self.diff_in(previous, Rect::new(0, 0, self.width, self.height))
+  }
+
+  // Like diff(), for the cells in `rect` only.
+  pub fn diff_in(&self, previous: &Canvas, rect: Rect) -> Vec<CellUpdate> {
+    let same_size = self.width == previous.width && self.height == previous.height;
+    let mut updates = Vec::new();
+    for y in rect.y..rect.bottom().min(self.height) {
+      for x in rect.x..rect.right().min(self.width) {
+        let cell = self.cells[y as usize * self.width as usize + x as usize];
+        if !same_size || previous.cell(x, y) != Some(cell) {
+          updates.push(CellUpdate {
+            x,
+            y,
+            c: cell.c,
+            fg: cell.fg,
+            bg: cell.bg,
+          });
+        }
+      }
+    }
+    updates
+  }
+
+  // The cell at (x, y) of the whole canvas, regardless of the current area.
+  pub fn get(&self, x: u16, y: u16) -> Option<char> {
+    self.cell(x, y).map(|cell| cell.c)
+  }
+
+  // The foreground and background colors of the cell at (x, y) of the whole canvas.
+  pub fn colors(&self, x: u16, y: u16) -> Option<(Color, Color)> {
+    self.cell(x, y).map(|cell| (cell.fg, cell.bg))
+  }
+
+  fn cell(&self, x: u16, y: u16) -> Option<Cell> {
+    if x < self.width && y < self.height {
+      Some(self.cells[y as usize * self.width as usize + x as usize])
+    } else {
+      None
+    }
+  }
+
+  pub fn put(&mut self, x: u16, y: u16, c: char) {
+    if x >= self.area.width || y >= self.area.height {
+      return;
+    }
+    let (x, y) = (self.area.x + x, self.area.y + y);
+    if self.clip.contains(x, y) {
+      let (fg, bg) = self.pen;
+      self.cells[y as usize * self.width as usize + x as usize] = Cell { c, fg, bg };
+    }
+  }
+
+  pub fn fill(&mut self, rect: Rect, c: char) {
+    for y in rect.y..rect.bottom() {
+      for x in rect.x..rect.right() {
+        self.put(x, y, c);
+      }
+    }
+  }
+
+  pub fn clear(&mut self) {
+    let area = Rect::new(0, 0, self.area.width, self.area.height);
+    self.fill(area, ' ');
+  }
+
+  // Writes `text` on a single line, one character per cell, cut off at the area's edge.
+  pub fn text(&mut self, x: u16, y: u16, text: &str) {
+    for (i, c) in text.chars().enumerate() {
+      let Ok(i) = u16::try_from(i) else { break };
+      self.put(x.saturating_add(i), y, c);
+    }
+  }
+
+  // Writes `text` centered in the row `y` of `rect`, cut off if it's too long.
+  pub fn label(&mut self, rect: Rect, y: u16, text: &str) {
+    self.aligned(rect, y, text, Align::Center);
+  }
+
+  // Writes `text` in the row `y` of `rect`, aligned as asked and cut off if it's too long.
+  pub fn aligned(&mut self, rect: Rect, y: u16, text: &str, align: Align) {
+    let len = text.chars().count();
+    let width = rect.width as usize;
+    let shown: String = text.chars().take(width).collect();
+    let room = width.saturating_sub(len);
+    let x = match align {
+      Align::Left => 0,
+      Align::Center => room / 2,
+      Align::Right => room,
+    };
+    self.text(rect.x + x as u16, rect.y + y, &shown);
+  }
+
+  pub fn border(&mut self, rect: Rect) {
+    self.border_with(rect, Border::Single);
+  }
+
+  pub fn border_with(&mut self, rect: Rect, border: Border) {
+    let Some([horizontal, vertical, top_left, top_right, bottom_left, bottom_right]) =
+      border.chars()
+    else {
+      return;
+    };
+    if rect.width == 0 || rect.height == 0 {
+      return;
+    }
+    let (right, bottom) = (rect.right() - 1, rect.bottom() - 1);
+    for x in rect.x..=right {
+      self.put(x, rect.y, horizontal);
+      self.put(x, bottom, horizontal);
+    }
+    for y in rect.y..=bottom {
+      self.put(rect.x, y, vertical);
+      self.put(right, y, vertical);
+    }
+    self.put(rect.x, rect.y, top_left);
+    self.put(right, rect.y, top_right);
+    self.put(rect.x, bottom, bottom_left);
+    self.put(right, bottom, bottom_right);
+  }
+
+  // A border around `rect` with `title` centered inside.
+  pub fn boxed(&mut self, rect: Rect, title: &str) {
+    self.border(rect);
+    if rect.width > 2 && rect.height > 2 {
+      let inner = Rect::new(rect.x + 1, rect.y + 1, rect.width - 2, rect.height - 2);
+      self.label(inner, (inner.height - 1) / 2, title);
+    }
+  }
+}
+
+// The canvas dumped as text, one line per row.
+impl fmt::Display for Canvas {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for row in self.cells.chunks(self.width.max(1) as usize) {
+      let line: String = row.iter().map(|cell| cell.c).collect();
+      writeln!(f, "{}", line)?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn draws_borders_and_labels() {
+    let mut canvas = Canvas::new(12, 3);
+    canvas.boxed(Rect::new(0, 0, 12, 3), "Hello");
+    assert_eq!(
+      "┌──────────┐\n│  Hello   │\n└──────────┘\n",
+      canvas.to_string()
+    );
+  }
+
+  #[test]
+  fn drawing_is_clipped_to_the_area() {
+    let mut canvas = Canvas::new(6, 2);
+    canvas.within(Rect::new(2, 1, 3, 5), |canvas| {
+      assert_eq!(Rect::new(2, 1, 3, 1), canvas.area());
+      canvas.text(0, 0, "abcdef");
+      canvas.text(0, 1, "below");
+    });
+    assert_eq!("      \n  abc \n", canvas.to_string());
+  }
+
+  #[test]
+  fn areas_outside_the_canvas_draw_nothing() {
+    let mut canvas = Canvas::new(4, 1);
+    canvas.within(Rect::new(10, 0, 3, 1), |canvas| canvas.text(0, 0, "abc"));
+    assert_eq!("    \n", canvas.to_string());
+  }
+
+  #[test]
+  fn long_labels_are_cut_off() {
+    let mut canvas = Canvas::new(4, 1);
+    canvas.label(Rect::new(0, 0, 4, 1), 0, "Submit");
+    assert_eq!("Subm\n", canvas.to_string());
+  }
+
+  #[test]
+  fn clipping_restricts_drawing() {
+    let mut canvas = Canvas::new(5, 1);
+    canvas.clipped(Rect::new(1, 0, 2, 1), |canvas| {
+      assert!(!canvas.is_visible(Rect::new(3, 0, 2, 1)));
+      canvas.text(0, 0, "abcde");
+    });
+    assert_eq!(" bc  \n", canvas.to_string());
+  }
+
+  #[test]
+  fn diff_lists_the_changed_cells() {
+    let previous = Canvas::new(3, 2);
+    let mut canvas = previous.clone();
+    canvas.text(1, 1, "ab");
+    canvas.put(0, 0, ' ');
+    // a cell that only changes color changes too
+    canvas.painted(Color::Red, Color::Default, |canvas| canvas.put(2, 0, ' '));
+    let update = |x, y, c, fg| CellUpdate {
+      x,
+      y,
+      c,
+      fg,
+      bg: Color::Default,
+    };
+    assert_eq!(
+      vec![
+        update(2, 0, ' ', Color::Red),
+        update(1, 1, 'a', Color::Default),
+        update(2, 1, 'b', Color::Default),
+      ],
+      canvas.diff(&previous)
+    );
+    assert_eq!(6, canvas.diff(&Canvas::new(2, 2)).len());
+  }
+
+  #[test]
+  fn text_can_be_aligned_and_painted() {
+    let mut canvas = Canvas::new(6, 3);
+    let row = Rect::new(0, 0, 6, 3);
+    canvas.aligned(row, 0, "ab", Align::Left);
+    canvas.painted(Color::Yellow, Color::Blue, |canvas| {
+      canvas.aligned(row, 1, "ab", Align::Center);
+    });
+    canvas.aligned(row, 2, "abcdefgh", Align::Right);
+    assert_eq!("ab    \n  ab  \nabcdef\n", canvas.to_string());
+    assert_eq!(Some((Color::Yellow, Color::Blue)), canvas.colors(2, 1));
+    assert_eq!(Some((Color::Default, Color::Default)), canvas.colors(1, 1));
+  }
+}
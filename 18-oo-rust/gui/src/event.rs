@@ -0,0 +1,47 @@
+// Input is delivered to the components as events. Mouse clicks go to the interactive component
+// under the cursor, keys to the focused one, and the focus moves between the interactive
+// components in the order they're drawn in. Moving the mouse marks the component under the
+// cursor as hovered.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+  Char(char),
+  Enter,
+  Space,
+  Backspace,
+  Delete,
+  Up,
+  Down,
+  Left,
+  Right,
+  Home,
+  End,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+  Key(Key),
+  // Screen coordinates when given to the Screen; the component receiving the click gets
+  // coordinates relative to its own top-left corner.
+  Click { x: u16, y: u16 },
+  // The mouse moved to (x, y), in screen coordinates.
+  Hover { x: u16, y: u16 },
+  FocusNext,
+  FocusPrev,
+}
+
+pub trait Interactive {
+  // Returns whether the event meant anything to the component.
+  fn handle_event(&mut self, event: &Event) -> bool;
+
+  // Lets the component know whether it's the focused one, e.g. to draw itself differently.
+  fn set_focused(&mut self, _focused: bool) {}
+
+  // Lets the component know whether the mouse is over it.
+  fn set_hovered(&mut self, _hovered: bool) {}
+
+  // Disabled components are left out when the focus moves, and don't get any events.
+  fn is_disabled(&self) -> bool {
+    false
+  }
+}
@@ -0,0 +1,422 @@
+// Containers arrange their children and are components themselves, so they can be nested to
+// build up a whole screen. Each child gets a rectangle of its preferred size; the containers
+// only decide where the rectangles go.
+use crate::{Accessible, Canvas, Draw, Rect, Role, Size, Theme};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Padding {
+  pub top: u16,
+  pub right: u16,
+  pub bottom: u16,
+  pub left: u16,
+}
+
+impl Padding {
+  pub fn all(n: u16) -> Padding {
+    Padding {
+      top: n,
+      right: n,
+      bottom: n,
+      left: n,
+    }
+  }
+
+  pub fn horizontal(&self) -> u16 {
+    self.left.saturating_add(self.right)
+  }
+
+  pub fn vertical(&self) -> u16 {
+    self.top.saturating_add(self.bottom)
+  }
+}
+
+// Where the layout put a component and its children, in screen coordinates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayoutNode {
+  pub rect: Rect,
+  pub children: Vec<LayoutNode>,
+}
+
+impl LayoutNode {
+  pub fn leaf(rect: Rect) -> LayoutNode {
+    LayoutNode {
+      rect,
+      children: Vec::new(),
+    }
+  }
+
+  // The rects of this node and all of its descendants, depth first.
+  pub fn rects(&self) -> Vec<Rect> {
+    let mut rects = vec![self.rect];
+    for child in self.children.iter() {
+      rects.extend(child.rects());
+    }
+    rects
+  }
+}
+
+// The size of a container whose children take up `rects` (relative to its top-left corner).
+fn bounding_size(rects: &[Rect], padding: Padding) -> Size {
+  let width = rects
+    .iter()
+    .map(|r| r.right())
+    .max()
+    .unwrap_or(padding.left);
+  let height = rects
+    .iter()
+    .map(|r| r.bottom())
+    .max()
+    .unwrap_or(padding.top);
+  Size::new(
+    width.saturating_add(padding.right),
+    height.saturating_add(padding.bottom),
+  )
+}
+
+fn draw_children(children: &[Box<dyn Draw>], rects: &[Rect], canvas: &mut Canvas) {
+  for (child, rect) in children.iter().zip(rects) {
+    if canvas.is_visible(*rect) {
+      canvas.within(*rect, |canvas| child.draw(canvas));
+    }
+  }
+}
+
+fn layout_children(children: &[Box<dyn Draw>], rects: &[Rect], rect: Rect) -> LayoutNode {
+  LayoutNode {
+    rect,
+    children: children
+      .iter()
+      .zip(rects)
+      .map(|(child, r)| {
+        child.layout(Rect::new(
+          rect.x.saturating_add(r.x),
+          rect.y.saturating_add(r.y),
+          r.width,
+          r.height,
+        ))
+      })
+      .collect(),
+  }
+}
+
+// Places children one after the other along a row (horizontal) or a column (vertical).
+fn linear_rects(
+  children: &[Box<dyn Draw>],
+  padding: Padding,
+  spacing: u16,
+  horizontal: bool,
+) -> Vec<Rect> {
+  let mut offset = if horizontal {
+    padding.left
+  } else {
+    padding.top
+  };
+  children
+    .iter()
+    .map(|child| {
+      let size = child.size();
+      let rect = if horizontal {
+        Rect::new(offset, padding.top, size.width, size.height)
+      } else {
+        Rect::new(padding.left, offset, size.width, size.height)
+      };
+      let length = if horizontal { size.width } else { size.height };
+      offset = offset.saturating_add(length).saturating_add(spacing);
+      rect
+    })
+    .collect()
+}
+
+// The parts every container shares: how big it is and how it draws and lays out its children
+// follow from the rects of the children, which is the only thing that differs between them.
+macro_rules! impl_container {
+  ($($container:ident),*) => {
+    $(
+      impl $container {
+        pub fn new() -> $container {
+          $container::default()
+        }
+
+        pub fn with_padding(mut self, padding: Padding) -> $container {
+          self.padding = padding;
+          self
+        }
+
+        // Lays `theme` over the one the container is under, for its children.
+        pub fn with_theme(mut self, theme: Theme) -> $container {
+          self.theme = Some(theme);
+          self
+        }
+      }
+
+      impl Draw for $container {
+        fn size(&self) -> Size {
+          bounding_size(&self.child_rects(), self.padding)
+        }
+
+        fn draw(&self, canvas: &mut Canvas) {
+          draw_children(&self.children, &self.child_rects(), canvas);
+        }
+
+        fn layout(&self, rect: Rect) -> LayoutNode {
+          layout_children(&self.children, &self.child_rects(), rect)
+        }
+
+        fn children(&self) -> &[Box<dyn Draw>] {
+          &self.children
+        }
+
+        fn children_mut(&mut self) -> &mut [Box<dyn Draw>] {
+          &mut self.children
+        }
+
+        fn as_accessible(&self) -> Option<&dyn Accessible> {
+          Some(self)
+        }
+
+        fn restyle(&mut self, theme: &Theme) {
+          let own = self.theme.as_ref().map(|own| own.over(theme));
+          let theme = own.as_ref().unwrap_or(theme);
+          for child in self.children.iter_mut() {
+            child.restyle(theme);
+          }
+        }
+      }
+
+      impl Accessible for $container {
+        fn role(&self) -> Role {
+          Role::Group
+        }
+      }
+    )*
+  };
+}
+
+// Children from left to right, `spacing` cells apart.
+#[derive(Default)]
+pub struct Row {
+  pub children: Vec<Box<dyn Draw>>,
+  pub padding: Padding,
+  pub theme: Option<Theme>,
+  pub spacing: u16,
+}
+
+impl Row {
+  pub fn with_spacing(mut self, spacing: u16) -> Row {
+    self.spacing = spacing;
+    self
+  }
+
+  fn child_rects(&self) -> Vec<Rect> {
+    linear_rects(&self.children, self.padding, self.spacing, true)
+  }
+}
+
+// Children from top to bottom, `spacing` cells apart.
+#[derive(Default)]
+pub struct Column {
+  pub children: Vec<Box<dyn Draw>>,
+  pub padding: Padding,
+  pub theme: Option<Theme>,
+  pub spacing: u16,
+}
+
+impl Column {
+  pub fn with_spacing(mut self, spacing: u16) -> Column {
+    self.spacing = spacing;
+    self
+  }
+
+  fn child_rects(&self) -> Vec<Rect> {
+    linear_rects(&self.children, self.padding, self.spacing, false)
+  }
+}
+
+// Children on top of each other, the last one drawn last.
+#[derive(Default)]
+pub struct Stack {
+  pub children: Vec<Box<dyn Draw>>,
+  pub padding: Padding,
+  pub theme: Option<Theme>,
+}
+
+impl Stack {
+  fn child_rects(&self) -> Vec<Rect> {
+    self
+      .children
+      .iter()
+      .map(|child| {
+        let size = child.size();
+        Rect::new(self.padding.left, self.padding.top, size.width, size.height)
+      })
+      .collect()
+  }
+}
+
+// Children at fixed positions, relative to the container's padding. The children and their
+// positions go in together, which is why neither can be changed from outside.
+#[derive(Default)]
+pub struct Absolute {
+  children: Vec<Box<dyn Draw>>,
+  // one for each child
+  positions: Vec<(u16, u16)>,
+  pub padding: Padding,
+  pub theme: Option<Theme>,
+}
+
+impl Absolute {
+  // Puts the child in the top-left corner.
+  pub fn child(self, child: impl Draw + 'static) -> Absolute {
+    self.child_at(0, 0, child)
+  }
+
+  pub fn child_at(mut self, x: u16, y: u16, child: impl Draw + 'static) -> Absolute {
+    self.push_at(x, y, Box::new(child));
+    self
+  }
+
+  pub(crate) fn push_at(&mut self, x: u16, y: u16, child: Box<dyn Draw>) {
+    self.children.push(child);
+    self.positions.push((x, y));
+  }
+
+  pub fn position(&self, i: usize) -> Option<(u16, u16)> {
+    self.positions.get(i).copied()
+  }
+
+  fn child_rects(&self) -> Vec<Rect> {
+    self
+      .children
+      .iter()
+      .zip(self.positions.iter())
+      .map(|(child, &(x, y))| {
+        let size = child.size();
+        Rect::new(
+          self.padding.left.saturating_add(x),
+          self.padding.top.saturating_add(y),
+          size.width,
+          size.height,
+        )
+      })
+      .collect()
+  }
+}
+
+impl_container!(Row, Column, Stack, Absolute);
+
+// Absolute has a child() of its own, which gives the child a position as well.
+macro_rules! impl_child {
+  ($($container:ident),*) => {
+    $(
+      impl $container {
+        pub fn child(mut self, child: impl Draw + 'static) -> $container {
+          self.children.push(Box::new(child));
+          self
+        }
+      }
+    )*
+  };
+}
+
+impl_child!(Row, Column, Stack);
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Button;
+
+  fn button(width: u16, height: u16) -> Button {
+    Button::new(width, height, "")
+  }
+
+  #[test]
+  fn row_places_children_side_by_side() {
+    let row = Row::new()
+      .with_padding(Padding::all(1))
+      .with_spacing(2)
+      .child(button(4, 3))
+      .child(button(6, 2));
+
+    assert_eq!(Size::new(1 + 4 + 2 + 6 + 1, 1 + 3 + 1), row.size());
+    assert_eq!(
+      vec![
+        Rect::new(10, 5, 14, 5),
+        Rect::new(11, 6, 4, 3),
+        Rect::new(17, 6, 6, 2),
+      ],
+      row.layout(Rect::new(10, 5, 14, 5)).rects()
+    );
+  }
+
+  #[test]
+  fn absolute_children_keep_their_own_positions() {
+    let absolute = Absolute::new()
+      .child(button(2, 1))
+      .child_at(5, 3, button(3, 1))
+      .child(button(1, 1));
+    assert_eq!(Some((5, 3)), absolute.position(1));
+    assert_eq!(
+      vec![
+        Rect::new(0, 0, 8, 4),
+        Rect::new(0, 0, 2, 1),
+        Rect::new(5, 3, 3, 1),
+        Rect::new(0, 0, 1, 1),
+      ],
+      absolute.layout(Rect::new(0, 0, 8, 4)).rects()
+    );
+  }
+
+  #[test]
+  fn nested_containers_form_a_layout_tree() {
+    let column = Column::new()
+      .with_spacing(1)
+      .child(button(8, 3))
+      .child(Row::new().child(button(3, 3)).child(button(5, 2)));
+
+    let layout = column.layout(Rect::new(0, 0, 8, 7));
+    assert_eq!(Size::new(8, 7), column.size());
+    assert_eq!(Rect::new(0, 4, 8, 3), layout.children[1].rect);
+    assert_eq!(
+      vec![Rect::new(0, 4, 3, 3), Rect::new(3, 4, 5, 2)],
+      layout.children[1].rects()[1..]
+    );
+  }
+
+  #[test]
+  fn stack_and_absolute_overlap_children() {
+    let stack = Stack::new().child(button(4, 4)).child(button(2, 6));
+    assert_eq!(Size::new(4, 6), stack.size());
+
+    let absolute = Absolute::new()
+      .with_padding(Padding {
+        left: 2,
+        ..Padding::default()
+      })
+      .child_at(0, 0, button(4, 2))
+      .child_at(3, 1, button(4, 2));
+    assert_eq!(Size::new(9, 3), absolute.size());
+    assert_eq!(
+      vec![
+        Rect::new(0, 0, 9, 3),
+        Rect::new(2, 0, 4, 2),
+        Rect::new(5, 1, 4, 2),
+      ],
+      absolute.layout(Rect::new(0, 0, 9, 3)).rects()
+    );
+  }
+
+  #[test]
+  fn containers_draw_children_in_their_rects() {
+    let row = Row::new()
+      .with_spacing(1)
+      .child(Button::new(5, 3, "A"))
+      .child(Button::new(5, 3, "B"));
+
+    let mut canvas = Canvas::new(11, 3);
+    row.draw(&mut canvas);
+    assert_eq!(
+      "┌───┐ ┌───┐\n│ A │ │ B │\n└───┘ └───┘\n",
+      canvas.to_string()
+    );
+  }
+}
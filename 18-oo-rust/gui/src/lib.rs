@@ -1,31 +1,736 @@
+mod a11y;
+mod canvas;
+mod event;
+mod layout;
+mod loader;
+mod screens;
+pub mod snapshot;
+mod style;
+mod widgets;
+
+pub use a11y::{Accessible, AccessibleNode, AccessibleState, LintIssue, Problem, Role};
+pub use canvas::{Align, Border, Canvas, CellUpdate, Color, Rect, Size};
+pub use event::{Event, Interactive, Key};
+pub use layout::{Absolute, Column, LayoutNode, Padding, Row, Stack};
+pub use loader::{Constructor, LoadError, LoadErrorKind, Node, Registry};
+pub use screens::{Components, HomogeneousScreen, TupleScreen};
+use std::collections::BTreeMap;
+pub use style::{ComputedStyle, StateStyles, Style, Theme, WidgetState};
+pub use widgets::{Button, Checkbox, Label, ListView, SelectBox, TextInput};
+
 pub trait Draw {
-  fn draw(&self);
+  // The room the component would like to get on the screen.
+  fn size(&self) -> Size;
+
+  // Draws the component in the canvas' current area, whose top-left corner is (0, 0).
+  fn draw(&self, canvas: &mut Canvas);
+
+  // Where the component and its children end up when it's given `rect`. Only containers have
+  // children, so that's all there is to the layout of any other component.
+  fn layout(&self, rect: Rect) -> LayoutNode {
+    LayoutNode::leaf(rect)
+  }
+
+  fn children(&self) -> &[Box<dyn Draw>] {
+    &[]
+  }
+
+  fn children_mut(&mut self) -> &mut [Box<dyn Draw>] {
+    &mut []
+  }
+
+  // Components that react to input hand out their Interactive side; the others are left alone
+  // by the event routing.
+  fn as_interactive(&mut self) -> Option<&mut dyn Interactive> {
+    None
+  }
+
+  // Components that can tell assistive technology what they are hand out their Accessible
+  // side; the others show up as unknown in the accessibility tree.
+  fn as_accessible(&self) -> Option<&dyn Accessible> {
+    None
+  }
+
+  // Whether the component changed since it was last drawn. Components that don't keep track
+  // are redrawn every time. Containers are never asked: their children are, and a change to
+  // the container itself changes the layout, which redraws the whole screen anyway.
+  fn is_dirty(&self) -> bool {
+    true
+  }
+
+  // Called once the component has been drawn.
+  fn mark_clean(&mut self) {}
+
+  // Hands the component the theme it's under. Containers pass it on to their children, with
+  // their own theme laid over it if they have one.
+  fn restyle(&mut self, _theme: &Theme) {}
 }
 
 pub struct Screen {
   pub components: Vec<Box<dyn Draw>>,
+  // The path (child indices from the screen down) of the focused component.
+  focus: Option<Vec<usize>>,
+  // The path of the component the mouse is over.
+  hover: Option<Vec<usize>>,
+  theme: Theme,
+  // The last frame render() produced, and the layout it was drawn with.
+  frame: Option<(Canvas, LayoutNode)>,
 }
 
 impl Screen {
-  pub fn draw(&self) {
-    for c in self.components.iter() {
-      c.draw();
+  // Draws the components below each other on a canvas just big enough to hold them all, and
+  // returns that canvas as the screen's frame.
+  pub fn draw(&self) -> Canvas {
+    let rects = self.component_rects();
+    let size = self.size();
+
+    let mut canvas = Canvas::new(size.width, size.height);
+    for (c, rect) in self.components.iter().zip(rects) {
+      canvas.within(rect, |canvas| c.draw(canvas));
     }
+    canvas
+  }
+
+  pub fn size(&self) -> Size {
+    bounding_size(&self.component_rects())
+  }
+
+  // The layout tree of the whole screen, rooted at the screen itself.
+  pub fn layout(&self) -> LayoutNode {
+    let size = self.size();
+    LayoutNode {
+      rect: Rect::new(0, 0, size.width, size.height),
+      children: self
+        .components
+        .iter()
+        .zip(self.component_rects())
+        .map(|(c, rect)| c.layout(rect))
+        .collect(),
+    }
+  }
+
+  fn component_rects(&self) -> Vec<Rect> {
+    stacked(self.components.iter().map(|c| c.size()))
   }
 
   pub fn new(components: Vec<Box<dyn Draw>>) -> Self {
-    Self { components }
+    let mut screen = Self {
+      components,
+      focus: None,
+      hover: None,
+      theme: Theme::default(),
+      frame: None,
+    };
+    screen.restyle();
+    screen
+  }
+
+  pub fn theme(&self) -> &Theme {
+    &self.theme
+  }
+
+  // Switches to another theme. The components whose style changes are redrawn by the next
+  // render().
+  pub fn set_theme(&mut self, theme: Theme) {
+    self.theme = theme;
+    self.restyle();
+  }
+
+  fn restyle(&mut self) {
+    for c in self.components.iter_mut() {
+      c.restyle(&self.theme);
+    }
+  }
+
+  // The frame as of the last render().
+  pub fn frame(&self) -> Option<&Canvas> {
+    self.frame.as_ref().map(|(canvas, _)| canvas)
+  }
+
+  // Brings the frame up to date and returns the cells that changed since the last render(),
+  // which is every cell the first time around. Only the regions of dirty components are
+  // redrawn, unless the layout changed since, which redraws the whole screen.
+  pub fn render(&mut self) -> Vec<CellUpdate> {
+    // components may have been added since the theme was last handed out
+    self.restyle();
+    let layout = self.layout();
+    let updates = match self.frame.take() {
+      Some((mut canvas, previous)) if previous == layout => {
+        let mut regions = Vec::new();
+        for (c, node) in self.components.iter().zip(&layout.children) {
+          dirty_regions(c.as_ref(), node, &mut regions);
+        }
+
+        // regions may overlap, in which case a cell's last update is the one that counts
+        let rects = self.component_rects();
+        let mut updates = BTreeMap::new();
+        for region in regions {
+          for update in self.redraw(&mut canvas, &rects, region) {
+            updates.insert((update.y, update.x), update);
+          }
+        }
+        self.frame = Some((canvas, layout));
+        updates.into_values().collect()
+      }
+      previous => {
+        let canvas = self.draw();
+        let updates = match previous {
+          Some((previous, _)) => canvas.diff(&previous),
+          None => canvas.diff(&Canvas::new(0, 0)),
+        };
+        self.frame = Some((canvas, layout));
+        updates
+      }
+    };
+
+    for c in self.components.iter_mut() {
+      mark_clean(c.as_mut());
+    }
+    updates
+  }
+
+  // Clears `region` of the frame and draws whatever overlaps it again.
+  fn redraw(&self, canvas: &mut Canvas, rects: &[Rect], region: Rect) -> Vec<CellUpdate> {
+    type Cell = (u16, u16, Option<char>, Option<(Color, Color)>);
+    let cells = |canvas: &Canvas| -> Vec<Cell> {
+      (region.y..region.bottom())
+        .flat_map(|y| (region.x..region.right()).map(move |x| (x, y)))
+        .map(|(x, y)| (x, y, canvas.get(x, y), canvas.colors(x, y)))
+        .collect()
+    };
+    let before = cells(canvas);
+    canvas.clipped(region, |canvas| {
+      canvas.fill(region, ' ');
+      for (c, rect) in self.components.iter().zip(rects) {
+        if canvas.is_visible(*rect) {
+          canvas.within(*rect, |canvas| c.draw(canvas));
+        }
+      }
+    });
+
+    let after = cells(canvas);
+    before
+      .into_iter()
+      .zip(after)
+      .filter(|(old, new)| old != new)
+      .filter_map(|(_, (x, y, c, colors))| {
+        let (fg, bg) = colors?;
+        Some(CellUpdate {
+          x,
+          y,
+          c: c?,
+          fg,
+          bg,
+        })
+      })
+      .collect()
+  }
+
+  // The accessibility tree of the whole screen, shaped like its layout.
+  pub fn accessibility_tree(&self) -> AccessibleNode {
+    let layout = self.layout();
+    let mut path = Vec::new();
+    let children = self
+      .components
+      .iter()
+      .zip(&layout.children)
+      .enumerate()
+      .map(|(i, (c, node))| {
+        path.push(i);
+        let child = accessible_node(c.as_ref(), node, &mut path);
+        path.pop();
+        child
+      })
+      .collect();
+    AccessibleNode {
+      role: Role::Screen,
+      name: None,
+      value: None,
+      state: AccessibleState::default(),
+      rect: layout.rect,
+      path: Vec::new(),
+      children,
+    }
+  }
+
+  pub fn focused(&self) -> Option<&[usize]> {
+    self.focus.as_deref()
+  }
+
+  // The component at `path`, which lists the child indices from the screen down.
+  pub fn component_mut(&mut self, path: &[usize]) -> Option<&mut dyn Draw> {
+    let (first, rest) = path.split_first()?;
+    let mut component = self.components.get_mut(*first)?.as_mut();
+    for i in rest {
+      component = component.children_mut().get_mut(*i)?.as_mut();
+    }
+    Some(component)
+  }
+
+  // The path of the topmost interactive component at (x, y), if there's one.
+  pub fn component_at(&mut self, x: u16, y: u16) -> Option<Vec<usize>> {
+    let layout = self.layout();
+    let mut path = Vec::new();
+    for (i, c) in self.components.iter_mut().enumerate().rev() {
+      path.push(i);
+      if hit(c.as_mut(), &layout.children[i], x, y, &mut path) {
+        return Some(path);
+      }
+      path.pop();
+    }
+    None
+  }
+
+  // Routes the event to the component it's meant for, returning whether it was handled.
+  pub fn handle_event(&mut self, event: Event) -> bool {
+    match event {
+      Event::Click { x, y } => {
+        let Some(path) = self.component_at(x, y) else {
+          return false;
+        };
+        if self.is_disabled(&path) {
+          return false;
+        }
+        self.focus(Some(path.clone()));
+        let rect = node_at(&self.layout(), &path).rect;
+        let click = Event::Click {
+          x: x - rect.x,
+          y: y - rect.y,
+        };
+        self.deliver(&path, &click)
+      }
+      Event::Hover { x, y } => {
+        let path = self.component_at(x, y);
+        if path != self.hover {
+          if let Some(old) = self.hover.take() {
+            if let Some(c) = self.component_mut(&old).and_then(|c| c.as_interactive()) {
+              c.set_hovered(false);
+            }
+          }
+          if let Some(new) = &path {
+            if let Some(c) = self.component_mut(new).and_then(|c| c.as_interactive()) {
+              c.set_hovered(true);
+            }
+          }
+          self.hover = path;
+        }
+        self.hover.is_some()
+      }
+      Event::FocusNext | Event::FocusPrev => {
+        let order = self.focus_order();
+        if order.is_empty() {
+          return false;
+        }
+        let current = self
+          .focus
+          .as_ref()
+          .and_then(|focus| order.iter().position(|path| path == focus));
+        let next = match (event, current) {
+          (Event::FocusNext, Some(i)) => (i + 1) % order.len(),
+          (Event::FocusNext, None) => 0,
+          (_, Some(i)) => (i + order.len() - 1) % order.len(),
+          (_, None) => order.len() - 1,
+        };
+        self.focus(Some(order[next].clone()));
+        true
+      }
+      Event::Key(_) => match self.focus.clone() {
+        Some(path) => self.deliver(&path, &event),
+        None => false,
+      },
+    }
+  }
+
+  // Paths of the interactive components, in the order Tab moves the focus through them.
+  fn focus_order(&mut self) -> Vec<Vec<usize>> {
+    let mut order = Vec::new();
+    for (i, c) in self.components.iter_mut().enumerate() {
+      collect_interactive(c.as_mut(), &mut vec![i], &mut order);
+    }
+    order
+  }
+
+  fn focus(&mut self, path: Option<Vec<usize>>) {
+    if let Some(old) = self.focus.take() {
+      if let Some(c) = self.component_mut(&old).and_then(|c| c.as_interactive()) {
+        c.set_focused(false);
+      }
+    }
+    if let Some(new) = &path {
+      if let Some(c) = self.component_mut(new).and_then(|c| c.as_interactive()) {
+        c.set_focused(true);
+      }
+    }
+    self.focus = path;
+  }
+
+  fn deliver(&mut self, path: &[usize], event: &Event) -> bool {
+    match self.component_mut(path).and_then(|c| c.as_interactive()) {
+      Some(c) if !c.is_disabled() => c.handle_event(event),
+      _ => false,
+    }
+  }
+
+  fn is_disabled(&mut self, path: &[usize]) -> bool {
+    self
+      .component_mut(path)
+      .and_then(|c| c.as_interactive())
+      .is_some_and(|c| c.is_disabled())
   }
 }
 
-pub struct Button {
-  pub width: u16,
-  pub height: u16,
-  pub label: String,
+// Rects for components of the given sizes, below each other, the way screens lay them out.
+fn stacked(sizes: impl Iterator<Item = Size>) -> Vec<Rect> {
+  let mut y = 0;
+  sizes
+    .map(|size| {
+      let rect = Rect::new(0, y, size.width, size.height);
+      y = y.saturating_add(size.height);
+      rect
+    })
+    .collect()
 }
 
-impl Draw for Button {
-  fn draw(&self) {
-    println!("Drawing a button...");
+// The size of a screen whose components take up `rects`.
+fn bounding_size(rects: &[Rect]) -> Size {
+  Size::new(
+    rects.iter().map(|r| r.right()).max().unwrap_or(0),
+    rects.iter().map(|r| r.bottom()).max().unwrap_or(0),
+  )
+}
+
+fn hit(c: &mut dyn Draw, node: &LayoutNode, x: u16, y: u16, path: &mut Vec<usize>) -> bool {
+  if !node.rect.contains(x, y) {
+    return false;
+  }
+  // later children are drawn over the earlier ones, so they're hit first
+  // a container that doesn't lay its children out has no nodes for them, and can't be hit
+  // through them either
+  let children = c.children_mut().iter_mut().zip(&node.children);
+  for (i, (child, child_node)) in children.enumerate().rev() {
+    path.push(i);
+    if hit(child.as_mut(), child_node, x, y, path) {
+      return true;
+    }
+    path.pop();
+  }
+  c.as_interactive().is_some()
+}
+
+// The rects of the dirty components without children, which are the ones doing the drawing.
+fn dirty_regions(c: &dyn Draw, node: &LayoutNode, regions: &mut Vec<Rect>) {
+  let children = c.children();
+  if children.is_empty() {
+    if c.is_dirty() {
+      regions.push(node.rect);
+    }
+    return;
+  }
+  for (child, child_node) in children.iter().zip(&node.children) {
+    dirty_regions(child.as_ref(), child_node, regions);
+  }
+}
+
+fn accessible_node(c: &dyn Draw, node: &LayoutNode, path: &mut Vec<usize>) -> AccessibleNode {
+  let mut children = Vec::new();
+  for (i, (child, child_node)) in c.children().iter().zip(&node.children).enumerate() {
+    path.push(i);
+    children.push(accessible_node(child.as_ref(), child_node, path));
+    path.pop();
+  }
+  let accessible = c.as_accessible();
+  AccessibleNode {
+    role: accessible.map_or(Role::Unknown, |a| a.role()),
+    name: accessible.and_then(|a| a.name()).map(str::to_string),
+    value: accessible.and_then(|a| a.value()),
+    state: accessible.map(|a| a.state()).unwrap_or_default(),
+    rect: node.rect,
+    path: path.clone(),
+    children,
+  }
+}
+
+fn mark_clean(c: &mut dyn Draw) {
+  c.mark_clean();
+  for child in c.children_mut() {
+    mark_clean(child.as_mut());
+  }
+}
+
+fn node_at<'a>(layout: &'a LayoutNode, path: &[usize]) -> &'a LayoutNode {
+  path.iter().fold(layout, |node, i| &node.children[*i])
+}
+
+fn collect_interactive(c: &mut dyn Draw, path: &mut Vec<usize>, order: &mut Vec<Vec<usize>>) {
+  if c.as_interactive().is_some_and(|c| !c.is_disabled()) {
+    order.push(path.clone());
+  }
+  for (i, child) in c.children_mut().iter_mut().enumerate() {
+    path.push(i);
+    collect_interactive(child.as_mut(), path, order);
+    path.pop();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::cell::Cell;
+  use std::rc::Rc;
+
+  fn button(width: u16, height: u16, label: &str) -> Box<Button> {
+    Box::new(Button::new(width, height, label))
+  }
+
+  // A button counting its clicks in `clicks`.
+  fn counting_button(width: u16, label: &str, clicks: &Rc<Cell<u32>>) -> Button {
+    let clicks = Rc::clone(clicks);
+    Button::new(width, 3, label).on_click(move || clicks.set(clicks.get() + 1))
+  }
+
+  #[test]
+  fn screen_stacks_components_into_one_frame() {
+    let screen = Screen::new(vec![button(8, 3, "OK"), button(10, 4, "Cancel")]);
+    let frame = screen.draw().to_string();
+    assert_eq!(
+      concat!(
+        "┌──────┐  \n",
+        "│  OK  │  \n",
+        "└──────┘  \n",
+        "┌────────┐\n",
+        "│ Cancel │\n",
+        "│        │\n",
+        "└────────┘\n",
+      ),
+      frame
+    );
+  }
+
+  #[test]
+  fn screen_layout_includes_nested_containers() {
+    let row = Row::new()
+      .with_spacing(1)
+      .child(*button(4, 3, "A"))
+      .child(*button(4, 3, "B"));
+    let screen = Screen::new(vec![button(6, 2, "Title"), Box::new(row)]);
+
+    let layout = screen.layout();
+    assert_eq!(Rect::new(0, 0, 9, 5), layout.rect);
+    assert_eq!(
+      vec![
+        Rect::new(0, 0, 9, 5),
+        Rect::new(0, 0, 6, 2),
+        Rect::new(0, 2, 9, 3),
+        Rect::new(0, 2, 4, 3),
+        Rect::new(5, 2, 4, 3),
+      ],
+      layout.rects()
+    );
+  }
+
+  // A container from outside the crate that has children but leaves the layout to the default.
+  struct Unlaid {
+    children: Vec<Box<dyn Draw>>,
+  }
+
+  impl Draw for Unlaid {
+    fn size(&self) -> Size {
+      Size::new(8, 3)
+    }
+
+    fn draw(&self, _canvas: &mut Canvas) {}
+
+    fn children(&self) -> &[Box<dyn Draw>] {
+      &self.children
+    }
+
+    fn children_mut(&mut self) -> &mut [Box<dyn Draw>] {
+      &mut self.children
+    }
+  }
+
+  #[test]
+  fn containers_without_a_layout_of_their_own_can_be_pointed_at() {
+    let clicks = Rc::new(Cell::new(0));
+    let unlaid = Unlaid {
+      children: vec![Box::new(counting_button(8, "OK", &clicks))],
+    };
+    let mut screen = Screen::new(vec![Box::new(unlaid)]);
+    assert!(!screen.handle_event(Event::Hover { x: 1, y: 1 }));
+    assert!(!screen.handle_event(Event::Click { x: 1, y: 1 }));
+    assert_eq!(None, screen.component_at(1, 1));
+    assert_eq!(0, clicks.get());
+  }
+
+  #[test]
+  fn empty_screen_has_an_empty_frame() {
+    assert_eq!("", Screen::new(vec![]).draw().to_string());
+  }
+
+  #[test]
+  fn clicks_go_to_the_component_under_the_cursor() {
+    let (ok, cancel) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+    let row = Row::new()
+      .with_spacing(1)
+      .child(counting_button(6, "OK", &ok))
+      .child(counting_button(8, "Cancel", &cancel));
+    let mut screen = Screen::new(vec![button(10, 2, "Title"), Box::new(row)]);
+
+    assert!(screen.handle_event(Event::Click { x: 8, y: 3 }));
+    assert_eq!((0, 1), (ok.get(), cancel.get()));
+    assert_eq!(Some(&[1, 1][..]), screen.focused());
+
+    // the spacing between the buttons isn't part of either
+    assert!(!screen.handle_event(Event::Click { x: 6, y: 3 }));
+    assert!(screen.handle_event(Event::Click { x: 0, y: 4 }));
+    assert_eq!((1, 1), (ok.get(), cancel.get()));
+  }
+
+  #[test]
+  fn keys_go_to_the_focused_component() {
+    let (ok, cancel) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+    let mut screen = Screen::new(vec![
+      Box::new(counting_button(6, "OK", &ok)),
+      Box::new(Column::new().child(counting_button(8, "Cancel", &cancel))),
+    ]);
+
+    assert!(!screen.handle_event(Event::Key(Key::Enter)));
+    screen.handle_event(Event::FocusNext);
+    screen.handle_event(Event::FocusNext);
+    assert_eq!(Some(&[1, 0][..]), screen.focused());
+    screen.handle_event(Event::Key(Key::Space));
+    assert_eq!((0, 1), (ok.get(), cancel.get()));
+
+    // the focus wraps around, in both directions
+    screen.handle_event(Event::FocusNext);
+    assert_eq!(Some(&[0][..]), screen.focused());
+    screen.handle_event(Event::FocusPrev);
+    assert_eq!(Some(&[1, 0][..]), screen.focused());
+    assert!(!screen.handle_event(Event::Key(Key::Char('x'))));
+  }
+
+  #[test]
+  fn focused_button_is_highlighted() {
+    let mut screen = Screen::new(vec![button(8, 3, "OK")]);
+    screen.handle_event(Event::FocusNext);
+    assert_eq!("╔══════╗\n║  OK  ║\n╚══════╝\n", screen.draw().to_string());
+  }
+
+  #[test]
+  fn render_only_updates_what_changed() {
+    let mut screen = Screen::new(vec![
+      button(6, 3, "OK"),
+      Box::new(
+        Row::new()
+          .child(*button(4, 3, "A"))
+          .child(*button(4, 3, "B")),
+      ),
+    ]);
+    assert_eq!(8 * 6, screen.render().len());
+    assert!(screen.render().is_empty());
+
+    // focusing B only redraws B, whose border is doubled
+    screen.handle_event(Event::Click { x: 5, y: 4 });
+    let updates = screen.render();
+    assert_eq!(10, updates.len());
+    assert!(updates.iter().all(|u| u.x >= 4 && u.y >= 3));
+    assert_eq!(
+      Some(&CellUpdate {
+        x: 4,
+        y: 3,
+        c: '╔',
+        fg: Color::Default,
+        bg: Color::Default,
+      }),
+      updates.first()
+    );
+    assert_eq!(
+      screen.draw().to_string(),
+      screen.frame().unwrap().to_string()
+    );
+  }
+
+  #[test]
+  fn layout_changes_redraw_the_whole_screen() {
+    let mut screen = Screen::new(vec![button(6, 3, "OK")]);
+    screen.render();
+    screen.components.insert(0, button(6, 1, "Title"));
+
+    let updates = screen.render();
+    assert_eq!(screen.draw().diff(&Canvas::new(6, 3)), updates);
+    assert_eq!(
+      screen.draw().to_string(),
+      screen.frame().unwrap().to_string()
+    );
+  }
+
+  #[test]
+  fn themes_can_be_swapped_at_runtime() {
+    let mut screen = Screen::new(vec![button(6, 3, "OK"), Box::new(Label::new(6, 1, "hi"))]);
+    screen.render();
+
+    screen.set_theme(Theme::dark());
+    let updates = screen.render();
+    assert_eq!(6 * 4, updates.len());
+    assert!(updates
+      .iter()
+      .all(|u| (u.fg, u.bg) == (Color::White, Color::Black)));
+    assert_eq!(
+      screen.draw().diff(&Canvas::new(0, 0)),
+      screen.frame().unwrap().diff(&Canvas::new(0, 0))
+    );
+
+    // the same theme again changes nothing
+    screen.set_theme(Theme::dark());
+    assert!(screen.render().is_empty());
+  }
+
+  #[test]
+  fn styles_cascade_from_screen_to_containers_to_widgets() {
+    let red_buttons = Theme::empty().with(
+      "Button",
+      StateStyles::new(Style::default().with_fg(Color::Red)),
+    );
+    let row = Row::new()
+      .with_theme(red_buttons)
+      .child(Button::new(4, 3, "A"))
+      .child(Button::new(4, 3, "B").with_style(Style::plain().with_fg(Color::Green)));
+    let screen = Screen::new(vec![button(4, 3, "C"), Box::new(row)]);
+
+    let canvas = screen.draw();
+    assert_eq!(Some((Color::Default, Color::Default)), canvas.colors(0, 0));
+    assert_eq!(Some((Color::Red, Color::Default)), canvas.colors(0, 3));
+    // B's own style wins over the row's theme, border included
+    assert_eq!(Some((Color::Green, Color::Default)), canvas.colors(5, 4));
+    assert_eq!(Some(' '), canvas.get(4, 3));
+  }
+
+  #[test]
+  fn hovered_and_disabled_widgets_get_their_styles() {
+    let mut disabled = Button::new(4, 3, "B");
+    disabled.set_disabled(true);
+    let mut screen = Screen::new(vec![button(4, 3, "A"), Box::new(disabled)]);
+    screen.set_theme(Theme::dark());
+
+    assert!(screen.handle_event(Event::Hover { x: 1, y: 1 }));
+    let canvas = screen.draw();
+    assert_eq!(Some((Color::Cyan, Color::Black)), canvas.colors(1, 1));
+    assert_eq!(Some((Color::Blue, Color::Black)), canvas.colors(1, 4));
+
+    // the disabled button can't be focused or clicked
+    assert!(!screen.handle_event(Event::Click { x: 1, y: 4 }));
+    screen.handle_event(Event::FocusNext);
+    screen.handle_event(Event::FocusNext);
+    assert_eq!(Some(&[0][..]), screen.focused());
+    assert_eq!(
+      Some((Color::Yellow, Color::Black)),
+      screen.draw().colors(1, 1)
+    );
+
+    assert!(!screen.handle_event(Event::Hover { x: 10, y: 10 }));
+    assert_eq!(
+      Some((Color::Yellow, Color::Black)),
+      screen.draw().colors(1, 1)
+    );
   }
 }
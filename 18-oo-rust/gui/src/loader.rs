@@ -0,0 +1,704 @@
+// Screens described in data files instead of code. A description lists the screen's components,
+// each one a table naming its `type` next to its properties, with the children of containers
+// nested under `children`:
+//
+//   [[components]]
+//   type = "Row"
+//   spacing = 1
+//
+//   [[components.children]]
+//   type = "Button"
+//   width = 8
+//   height = 3
+//   label = "OK"
+//
+// JSON descriptions have the same shape, and both are read into the same tree before any
+// component is built. Which constructor builds which `type` is up to the Registry, so that
+// components defined outside this crate can be described just like the built-in ones. That's
+// also the way to hook up callbacks, which a data file can't describe: register a constructor
+// of your own for the type, attaching them to the components it builds.
+use crate::{
+  Absolute, Align, Border, Button, Checkbox, Color, Column, Draw, Label, ListView, Padding, Row,
+  Screen, SelectBox, Stack, Style, TextInput,
+};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadErrorKind {
+  // the file isn't valid TOML or JSON to begin with
+  Syntax(String),
+  NotAComponent,
+  UnknownType(String),
+  MissingField(String),
+  InvalidField {
+    field: String,
+    expected: &'static str,
+  },
+  // anything else a constructor doesn't like about its node
+  Other(String),
+}
+
+// What went wrong, and where: `path` leads from the top of the description to the offending
+// node, as in `components[1].children[0]`. It's empty for problems with the file as a whole.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadError {
+  pub path: String,
+  pub kind: LoadErrorKind,
+}
+
+impl fmt::Display for LoadErrorKind {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      LoadErrorKind::Syntax(message) => write!(f, "invalid description: {}", message),
+      LoadErrorKind::NotAComponent => write!(f, "expected a table describing a component"),
+      LoadErrorKind::UnknownType(name) => write!(f, "unknown component type `{}`", name),
+      LoadErrorKind::MissingField(field) => write!(f, "missing field `{}`", field),
+      LoadErrorKind::InvalidField { field, expected } => {
+        write!(f, "field `{}` should be {}", field, expected)
+      }
+      LoadErrorKind::Other(message) => write!(f, "{}", message),
+    }
+  }
+}
+
+impl fmt::Display for LoadError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if self.path.is_empty() {
+      self.kind.fmt(f)
+    } else {
+      write!(f, "{}: {}", self.path, self.kind)
+    }
+  }
+}
+
+impl Error for LoadError {}
+
+// One component of a description, along with where it is in there.
+pub struct Node<'a> {
+  properties: &'a Map<String, Value>,
+  path: String,
+}
+
+impl<'a> Node<'a> {
+  fn new(value: &'a Value, path: String) -> Result<Node<'a>, LoadError> {
+    match value.as_object() {
+      Some(properties) => Ok(Node { properties, path }),
+      None => Err(LoadError {
+        path,
+        kind: LoadErrorKind::NotAComponent,
+      }),
+    }
+  }
+
+  pub fn path(&self) -> &str {
+    &self.path
+  }
+
+  // An error about this node, for constructors with requirements of their own.
+  pub fn error(&self, kind: LoadErrorKind) -> LoadError {
+    LoadError {
+      path: self.path.clone(),
+      kind,
+    }
+  }
+
+  fn invalid(&self, field: &str, expected: &'static str) -> LoadError {
+    self.error(LoadErrorKind::InvalidField {
+      field: field.to_string(),
+      expected,
+    })
+  }
+
+  // The raw value of a property, for properties that don't fit the getters below.
+  pub fn get(&self, field: &str) -> Option<&'a Value> {
+    self.properties.get(field)
+  }
+
+  fn required<T>(&self, field: &str, value: Result<Option<T>, LoadError>) -> Result<T, LoadError> {
+    value?.ok_or_else(|| self.error(LoadErrorKind::MissingField(field.to_string())))
+  }
+
+  pub fn opt_u16(&self, field: &str) -> Result<Option<u16>, LoadError> {
+    let Some(value) = self.get(field) else {
+      return Ok(None);
+    };
+    match value.as_u64().and_then(|n| u16::try_from(n).ok()) {
+      Some(n) => Ok(Some(n)),
+      None => Err(self.invalid(field, "a whole number from 0 to 65535")),
+    }
+  }
+
+  pub fn u16(&self, field: &str) -> Result<u16, LoadError> {
+    self.required(field, self.opt_u16(field))
+  }
+
+  pub fn opt_str(&self, field: &str) -> Result<Option<&'a str>, LoadError> {
+    match self.get(field) {
+      None => Ok(None),
+      Some(Value::String(s)) => Ok(Some(s)),
+      Some(_) => Err(self.invalid(field, "a string")),
+    }
+  }
+
+  pub fn str(&self, field: &str) -> Result<&'a str, LoadError> {
+    self.required(field, self.opt_str(field))
+  }
+
+  pub fn opt_bool(&self, field: &str) -> Result<Option<bool>, LoadError> {
+    match self.get(field) {
+      None => Ok(None),
+      Some(Value::Bool(b)) => Ok(Some(*b)),
+      Some(_) => Err(self.invalid(field, "true or false")),
+    }
+  }
+
+  // A list of strings, which is empty when the field is left out.
+  pub fn strings(&self, field: &str) -> Result<Vec<String>, LoadError> {
+    let Some(value) = self.get(field) else {
+      return Ok(Vec::new());
+    };
+    value
+      .as_array()
+      .and_then(|items| {
+        items
+          .iter()
+          .map(|item| item.as_str().map(str::to_string))
+          .collect()
+      })
+      .ok_or_else(|| self.invalid(field, "a list of strings"))
+  }
+
+  // Padding is either one number for all four sides, or a table with any of `top`, `right`,
+  // `bottom` and `left`.
+  pub fn padding(&self) -> Result<Padding, LoadError> {
+    const EXPECTED: &str = "a whole number or a table of top, right, bottom and left";
+    let sides = match self.get("padding") {
+      None => return Ok(Padding::default()),
+      Some(Value::Object(sides)) => sides,
+      Some(value) => {
+        return match value.as_u64().and_then(|n| u16::try_from(n).ok()) {
+          Some(n) => Ok(Padding::all(n)),
+          None => Err(self.invalid("padding", EXPECTED)),
+        };
+      }
+    };
+
+    let mut padding = Padding::default();
+    for (side, value) in sides {
+      let n = value
+        .as_u64()
+        .and_then(|n| u16::try_from(n).ok())
+        .ok_or_else(|| self.invalid("padding", EXPECTED))?;
+      match side.as_str() {
+        "top" => padding.top = n,
+        "right" => padding.right = n,
+        "bottom" => padding.bottom = n,
+        "left" => padding.left = n,
+        _ => return Err(self.invalid("padding", EXPECTED)),
+      }
+    }
+    Ok(padding)
+  }
+
+  // A widget's own style, from the `border`, `padding`, `fg`, `bg` and `align` fields that are
+  // there. The theme takes care of the rest.
+  pub fn style(&self) -> Result<Style, LoadError> {
+    let mut style = Style::default();
+    if let Some(border) = self.opt_str("border")? {
+      style.border = Some(match border {
+        "none" => Border::None,
+        "single" => Border::Single,
+        "double" => Border::Double,
+        _ => return Err(self.invalid("border", "one of none, single or double")),
+      });
+    }
+    if self.get("padding").is_some() {
+      style.padding = Some(self.padding()?);
+    }
+    style.fg = self.opt_color("fg")?;
+    style.bg = self.opt_color("bg")?;
+    if let Some(align) = self.opt_str("align")? {
+      style.align = Some(match align {
+        "left" => Align::Left,
+        "center" => Align::Center,
+        "right" => Align::Right,
+        _ => return Err(self.invalid("align", "one of left, center or right")),
+      });
+    }
+    Ok(style)
+  }
+
+  pub fn opt_color(&self, field: &str) -> Result<Option<Color>, LoadError> {
+    let Some(color) = self.opt_str(field)? else {
+      return Ok(None);
+    };
+    let color = match color {
+      "default" => Color::Default,
+      "black" => Color::Black,
+      "red" => Color::Red,
+      "green" => Color::Green,
+      "yellow" => Color::Yellow,
+      "blue" => Color::Blue,
+      "magenta" => Color::Magenta,
+      "cyan" => Color::Cyan,
+      "white" => Color::White,
+      _ => return Err(self.invalid(field, "the name of a color, as in red or blue")),
+    };
+    Ok(Some(color))
+  }
+
+  // The nodes nested under `children`, if there are any.
+  pub fn children(&self) -> Result<Vec<Node<'a>>, LoadError> {
+    let Some(children) = self.get("children") else {
+      return Ok(Vec::new());
+    };
+    let children = children
+      .as_array()
+      .ok_or_else(|| self.invalid("children", "a list of components"))?;
+    children
+      .iter()
+      .enumerate()
+      .map(|(i, child)| Node::new(child, format!("{}.children[{}]", self.path, i)))
+      .collect()
+  }
+}
+
+// Builds a component from its node. Containers get the registry too, to build their children.
+pub type Constructor = Box<dyn Fn(&Node, &Registry) -> Result<Box<dyn Draw>, LoadError>>;
+
+pub struct Registry {
+  constructors: HashMap<String, Constructor>,
+}
+
+impl Default for Registry {
+  fn default() -> Registry {
+    Registry::new()
+  }
+}
+
+impl Registry {
+  // A registry that knows all the components of this crate.
+  pub fn new() -> Registry {
+    let mut registry = Registry::empty();
+    registry.register("Button", |node, _| {
+      let mut button = Button::new(node.u16("width")?, node.u16("height")?, node.str("label")?);
+      button.set_disabled(node.opt_bool("disabled")?.unwrap_or(false));
+      Ok(Box::new(button.with_style(node.style()?)))
+    });
+    registry.register("Label", |node, _| {
+      let label = Label::new(node.u16("width")?, node.u16("height")?, node.str("text")?);
+      Ok(Box::new(label.with_style(node.style()?)))
+    });
+    registry.register("Checkbox", |node, _| {
+      let mut checkbox = Checkbox::new(node.u16("width")?, node.u16("height")?, node.str("label")?);
+      checkbox.checked = node.opt_bool("checked")?.unwrap_or(false);
+      checkbox.set_disabled(node.opt_bool("disabled")?.unwrap_or(false));
+      Ok(Box::new(checkbox.with_style(node.style()?)))
+    });
+    registry.register("TextInput", |node, _| {
+      let mut input = TextInput::new(node.u16("width")?, node.u16("height")?);
+      if let Some(max_length) = node.opt_u16("max_length")? {
+        input = input.with_max_length(max_length.into());
+      }
+      if let Some(value) = node.opt_str("value")? {
+        input.set_value(value);
+      }
+      input.set_disabled(node.opt_bool("disabled")?.unwrap_or(false));
+      Ok(Box::new(input.with_style(node.style()?)))
+    });
+    registry.register("SelectBox", |node, _| {
+      let options = node.strings("options")?;
+      let count = options.len();
+      let mut select = SelectBox::new(node.u16("width")?, node.u16("height")?, options);
+      if let Some(selected) = node.opt_u16("selected")? {
+        // select() also says no to the option that's selected already, which is the first
+        if usize::from(selected) >= count {
+          return Err(node.invalid("selected", "the index of one of the options"));
+        }
+        select.select(selected.into());
+      }
+      select.set_disabled(node.opt_bool("disabled")?.unwrap_or(false));
+      Ok(Box::new(select.with_style(node.style()?)))
+    });
+    registry.register("ListView", |node, _| {
+      let items = node.strings("items")?;
+      let mut list = ListView::new(node.u16("width")?, node.u16("height")?, items);
+      if let Some(selected) = node.opt_u16("selected")? {
+        if !list.select(selected.into()) {
+          return Err(node.invalid("selected", "the index of one of the items"));
+        }
+      }
+      list.set_disabled(node.opt_bool("disabled")?.unwrap_or(false));
+      Ok(Box::new(list.with_style(node.style()?)))
+    });
+    registry.register("Row", |node, registry| {
+      let mut row = Row::new().with_padding(node.padding()?);
+      row.spacing = node.opt_u16("spacing")?.unwrap_or(0);
+      row.children = registry.build_children(node)?;
+      Ok(Box::new(row))
+    });
+    registry.register("Column", |node, registry| {
+      let mut column = Column::new().with_padding(node.padding()?);
+      column.spacing = node.opt_u16("spacing")?.unwrap_or(0);
+      column.children = registry.build_children(node)?;
+      Ok(Box::new(column))
+    });
+    registry.register("Stack", |node, registry| {
+      let mut stack = Stack::new().with_padding(node.padding()?);
+      stack.children = registry.build_children(node)?;
+      Ok(Box::new(stack))
+    });
+    // the children of an Absolute say where they go, with `x` and `y`
+    registry.register("Absolute", |node, registry| {
+      let mut absolute = Absolute::new().with_padding(node.padding()?);
+      for child in node.children()? {
+        let x = child.opt_u16("x")?.unwrap_or(0);
+        let y = child.opt_u16("y")?.unwrap_or(0);
+        absolute.push_at(x, y, registry.build(&child)?);
+      }
+      Ok(Box::new(absolute))
+    });
+    registry
+  }
+
+  // A registry that doesn't know any components yet.
+  pub fn empty() -> Registry {
+    Registry {
+      constructors: HashMap::new(),
+    }
+  }
+
+  // Makes `constructor` build the components of type `name`, replacing whatever built them
+  // before.
+  pub fn register(
+    &mut self,
+    name: &str,
+    constructor: impl Fn(&Node, &Registry) -> Result<Box<dyn Draw>, LoadError> + 'static,
+  ) {
+    self
+      .constructors
+      .insert(name.to_string(), Box::new(constructor));
+  }
+
+  pub fn build(&self, node: &Node) -> Result<Box<dyn Draw>, LoadError> {
+    let name = node.str("type")?;
+    match self.constructors.get(name) {
+      Some(constructor) => constructor(node, self),
+      None => Err(node.error(LoadErrorKind::UnknownType(name.to_string()))),
+    }
+  }
+
+  pub fn build_children(&self, node: &Node) -> Result<Vec<Box<dyn Draw>>, LoadError> {
+    node
+      .children()?
+      .iter()
+      .map(|child| self.build(child))
+      .collect()
+  }
+
+  pub fn load_toml(&self, description: &str) -> Result<Screen, LoadError> {
+    let value = toml::from_str(description).map_err(|e| syntax_error(e.message()))?;
+    self.load(&value)
+  }
+
+  pub fn load_json(&self, description: &str) -> Result<Screen, LoadError> {
+    let value = serde_json::from_str(description).map_err(|e| syntax_error(&e.to_string()))?;
+    self.load(&value)
+  }
+
+  fn load(&self, description: &Value) -> Result<Screen, LoadError> {
+    let components = match description.get("components") {
+      Some(Value::Array(components)) => components,
+      Some(_) => {
+        return Err(LoadError {
+          path: String::new(),
+          kind: LoadErrorKind::InvalidField {
+            field: "components".to_string(),
+            expected: "a list of components",
+          },
+        })
+      }
+      None => {
+        return Err(LoadError {
+          path: String::new(),
+          kind: LoadErrorKind::MissingField("components".to_string()),
+        })
+      }
+    };
+
+    let components = components
+      .iter()
+      .enumerate()
+      .map(|(i, c)| self.build(&Node::new(c, format!("components[{}]", i))?))
+      .collect::<Result<_, _>>()?;
+    Ok(Screen::new(components))
+  }
+}
+
+fn syntax_error(message: &str) -> LoadError {
+  LoadError {
+    path: String::new(),
+    kind: LoadErrorKind::Syntax(message.trim_end().to_string()),
+  }
+}
+
+impl Screen {
+  // Builds the screen a TOML description describes, out of the components of this crate. Use a
+  // Registry to load components of your own.
+  pub fn from_toml(description: &str) -> Result<Screen, LoadError> {
+    Registry::new().load_toml(description)
+  }
+
+  pub fn from_json(description: &str) -> Result<Screen, LoadError> {
+    Registry::new().load_json(description)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Canvas, Size};
+
+  const DIALOG: &str = r#"
+    [[components]]
+    type = "Label"
+    width = 13
+    height = 1
+    text = "Save changes?"
+
+    [[components]]
+    type = "Row"
+    spacing = 1
+
+    [[components.children]]
+    type = "Button"
+    width = 6
+    height = 3
+    label = "Yes"
+
+    [[components.children]]
+    type = "Button"
+    width = 6
+    height = 3
+    label = "No"
+    border = "double"
+  "#;
+
+  #[test]
+  fn toml_and_json_describe_the_same_screen() {
+    let json = r#"{
+      "components": [
+        { "type": "Label", "width": 13, "height": 1, "text": "Save changes?" },
+        { "type": "Row", "spacing": 1, "children": [
+          { "type": "Button", "width": 6, "height": 3, "label": "Yes" },
+          { "type": "Button", "width": 6, "height": 3, "label": "No", "border": "double" }
+        ] }
+      ]
+    }"#;
+
+    let screen = Screen::from_toml(DIALOG).unwrap();
+    assert_eq!(
+      concat!(
+        "Save changes?\n",
+        "┌────┐ ╔════╗\n",
+        "│Yes │ ║ No ║\n",
+        "└────┘ ╚════╝\n",
+      ),
+      screen.draw().to_string()
+    );
+    assert_eq!(
+      screen.draw().to_string(),
+      Screen::from_json(json).unwrap().draw().to_string()
+    );
+  }
+
+  #[test]
+  fn widgets_get_their_properties() {
+    let screen = Screen::from_toml(
+      r#"
+      [[components]]
+      type = "Checkbox"
+      width = 8
+      height = 1
+      label = "Wrap"
+      checked = true
+
+      [[components]]
+      type = "SelectBox"
+      width = 7
+      height = 4
+      options = ["one", "two", "three"]
+      selected = 1
+      padding = { left = 1 }
+
+      [[components]]
+      type = "TextInput"
+      width = 7
+      height = 1
+      border = "none"
+      max_length = 3
+      value = "hello"
+      fg = "red"
+      disabled = true
+      "#,
+    )
+    .unwrap();
+    assert_eq!(
+      Some((Color::Red, Color::Default)),
+      screen.draw().colors(0, 5)
+    );
+    assert_eq!(
+      concat!(
+        " [x] Wra\n",
+        "┌─────┐ \n",
+        "│  one│ \n",
+        "│ >two│ \n",
+        "└─────┘ \n",
+        "hel     \n",
+      ),
+      screen.draw().to_string()
+    );
+  }
+
+  #[test]
+  fn select_boxes_can_start_on_any_option() {
+    let select_box = |selected: u16| {
+      Screen::from_toml(&format!(
+        r#"
+        [[components]]
+        type = "SelectBox"
+        width = 7
+        height = 4
+        options = ["one", "two"]
+        selected = {}
+        "#,
+        selected
+      ))
+    };
+    for (selected, option) in [(0, "one"), (1, "two")] {
+      let screen = select_box(selected).unwrap();
+      assert_eq!(
+        Some(option.to_string()),
+        screen.accessibility_tree().children[0].value
+      );
+    }
+    assert_eq!(
+      "components[0]: field `selected` should be the index of one of the options",
+      select_box(2).err().unwrap().to_string()
+    );
+  }
+
+  #[test]
+  fn absolute_children_say_where_they_go() {
+    let screen = Screen::from_json(
+      r#"{ "components": [
+        { "type": "Absolute", "padding": 1, "children": [
+          { "type": "Label", "width": 2, "height": 1, "text": "ab" },
+          { "type": "Label", "width": 2, "height": 1, "text": "cd", "x": 3, "y": 1 }
+        ] }
+      ] }"#,
+    )
+    .unwrap();
+    assert_eq!(Size::new(1 + 5 + 1, 1 + 2 + 1), screen.size());
+    assert_eq!(
+      "       \n ab    \n    cd \n       \n",
+      screen.draw().to_string()
+    );
+  }
+
+  // A component from outside the crate: a bar filled up to `percent`.
+  struct Gauge {
+    width: u16,
+    percent: u16,
+  }
+
+  impl Draw for Gauge {
+    fn size(&self) -> Size {
+      Size::new(self.width, 1)
+    }
+
+    fn draw(&self, canvas: &mut Canvas) {
+      let filled = u32::from(self.width) * u32::from(self.percent) / 100;
+      for x in 0..self.width {
+        canvas.put(x, 0, if u32::from(x) < filled { '#' } else { '.' });
+      }
+    }
+  }
+
+  #[test]
+  fn registered_components_can_be_loaded() {
+    let mut registry = Registry::new();
+    registry.register("Gauge", |node, _| {
+      let percent = node.u16("percent")?;
+      if percent > 100 {
+        return Err(node.error(LoadErrorKind::Other("percent over 100".to_string())));
+      }
+      Ok(Box::new(Gauge {
+        width: node.u16("width")?,
+        percent,
+      }))
+    });
+
+    let description = r#"{ "components": [
+      { "type": "Column", "children": [ { "type": "Gauge", "width": 8, "percent": 50 } ] }
+    ] }"#;
+    let screen = registry.load_json(description).unwrap();
+    assert_eq!("####....\n", screen.draw().to_string());
+
+    let error = registry
+      .load_json(r#"{ "components": [ { "type": "Gauge", "width": 8, "percent": 150 } ] }"#)
+      .err()
+      .unwrap();
+    assert_eq!("components[0]: percent over 100", error.to_string());
+    assert!(Screen::from_json(description).is_err());
+  }
+
+  #[test]
+  fn errors_lead_to_the_offending_node() {
+    let error = |description: &str| Screen::from_json(description).err().unwrap().to_string();
+
+    assert_eq!(
+      "components[1].children[1]: unknown component type `Buton`",
+      error(
+        r#"{ "components": [
+          { "type": "Label", "width": 2, "height": 1, "text": "hi" },
+          { "type": "Row", "children": [
+            { "type": "Button", "width": 4, "height": 3, "label": "A" },
+            { "type": "Buton", "width": 4, "height": 3, "label": "B" }
+          ] }
+        ] }"#
+      )
+    );
+    assert_eq!(
+      "components[0].children[0]: missing field `width`",
+      error(r#"{ "components": [ { "type": "Stack", "children": [ { "type": "Button" } ] } ] }"#)
+    );
+    assert_eq!(
+      "components[0]: field `width` should be a whole number from 0 to 65535",
+      error(r#"{ "components": [ { "type": "Label", "width": -1 } ] }"#)
+    );
+    assert_eq!(
+      "components[0].children[0]: expected a table describing a component",
+      error(r#"{ "components": [ { "type": "Row", "children": [ 3 ] } ] }"#)
+    );
+    assert_eq!(
+      "missing field `components`",
+      error(r#"{ "component": [] }"#)
+    );
+    assert!(error("{ \"components\": [").starts_with("invalid description: "));
+
+    let toml_error = Screen::from_toml("[[components]]\ntype = \"Label\"\nwidth = 3\nheight = 1\n")
+      .err()
+      .unwrap();
+    assert_eq!(
+      LoadError {
+        path: "components[0]".to_string(),
+        kind: LoadErrorKind::MissingField("text".to_string()),
+      },
+      toml_error
+    );
+  }
+}
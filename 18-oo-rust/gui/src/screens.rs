@@ -0,0 +1,184 @@
+// Screens that know the types of their components at compile time, so that drawing them doesn't
+// go through a vtable for each component. They lay their components out just like Screen does,
+// below each other, but that's all they do: input and incremental rendering are left to Screen.
+//
+// A HomogeneousScreen holds any number of components of one type. A TupleScreen holds a few
+// components of different types in a tuple, which is as far as generics go towards what
+// `Vec<Box<dyn Draw>>` does: the number and types of the components are fixed by the program.
+use crate::{bounding_size, stacked, Canvas, Draw, LayoutNode, Rect, Size, Theme};
+
+pub struct HomogeneousScreen<T: Draw> {
+  pub components: Vec<T>,
+}
+
+impl<T: Draw> HomogeneousScreen<T> {
+  pub fn new(components: Vec<T>) -> HomogeneousScreen<T> {
+    let mut screen = HomogeneousScreen { components };
+    screen.set_theme(&Theme::default());
+    screen
+  }
+
+  pub fn set_theme(&mut self, theme: &Theme) {
+    for c in self.components.iter_mut() {
+      c.restyle(theme);
+    }
+  }
+
+  fn component_rects(&self) -> Vec<Rect> {
+    stacked(self.components.iter().map(|c| c.size()))
+  }
+
+  pub fn size(&self) -> Size {
+    bounding_size(&self.component_rects())
+  }
+
+  pub fn layout(&self) -> LayoutNode {
+    let rects = self.component_rects();
+    let size = bounding_size(&rects);
+    LayoutNode {
+      rect: Rect::new(0, 0, size.width, size.height),
+      children: self
+        .components
+        .iter()
+        .zip(rects)
+        .map(|(c, rect)| c.layout(rect))
+        .collect(),
+    }
+  }
+
+  pub fn draw(&self) -> Canvas {
+    let rects = self.component_rects();
+    let size = bounding_size(&rects);
+    let mut canvas = Canvas::new(size.width, size.height);
+    for (c, rect) in self.components.iter().zip(rects) {
+      canvas.within(rect, |canvas| c.draw(canvas));
+    }
+    canvas
+  }
+}
+
+// What a TupleScreen needs of its components, which tuples of up to 12 components have.
+pub trait Components {
+  fn sizes(&self) -> Vec<Size>;
+
+  // Draws each component in its rect.
+  fn draw_all(&self, canvas: &mut Canvas, rects: &[Rect]);
+
+  fn layout_all(&self, rects: &[Rect]) -> Vec<LayoutNode>;
+
+  fn restyle_all(&mut self, theme: &Theme);
+}
+
+macro_rules! impl_components {
+  ($($component:ident $i:tt),+) => {
+    impl<$($component: Draw),+> Components for ($($component,)+) {
+      fn sizes(&self) -> Vec<Size> {
+        vec![$(self.$i.size()),+]
+      }
+
+      fn draw_all(&self, canvas: &mut Canvas, rects: &[Rect]) {
+        $(canvas.within(rects[$i], |canvas| self.$i.draw(canvas));)+
+      }
+
+      fn layout_all(&self, rects: &[Rect]) -> Vec<LayoutNode> {
+        vec![$(self.$i.layout(rects[$i])),+]
+      }
+
+      fn restyle_all(&mut self, theme: &Theme) {
+        $(self.$i.restyle(theme);)+
+      }
+    }
+  };
+}
+
+impl_components!(A 0);
+impl_components!(A 0, B 1);
+impl_components!(A 0, B 1, C 2);
+impl_components!(A 0, B 1, C 2, D 3);
+impl_components!(A 0, B 1, C 2, D 3, E 4);
+impl_components!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_components!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_components!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+impl_components!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+impl_components!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+impl_components!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+impl_components!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
+
+pub struct TupleScreen<C: Components> {
+  pub components: C,
+}
+
+impl<C: Components> TupleScreen<C> {
+  pub fn new(components: C) -> TupleScreen<C> {
+    let mut screen = TupleScreen { components };
+    screen.set_theme(&Theme::default());
+    screen
+  }
+
+  pub fn set_theme(&mut self, theme: &Theme) {
+    self.components.restyle_all(theme);
+  }
+
+  fn component_rects(&self) -> Vec<Rect> {
+    stacked(self.components.sizes().into_iter())
+  }
+
+  pub fn size(&self) -> Size {
+    bounding_size(&self.component_rects())
+  }
+
+  pub fn layout(&self) -> LayoutNode {
+    let rects = self.component_rects();
+    let size = bounding_size(&rects);
+    LayoutNode {
+      rect: Rect::new(0, 0, size.width, size.height),
+      children: self.components.layout_all(&rects),
+    }
+  }
+
+  pub fn draw(&self) -> Canvas {
+    let rects = self.component_rects();
+    let size = bounding_size(&rects);
+    let mut canvas = Canvas::new(size.width, size.height);
+    self.components.draw_all(&mut canvas, &rects);
+    canvas
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Button, Checkbox, Label, Row, Screen};
+
+  #[test]
+  fn all_screens_draw_the_same_frame() {
+    let buttons = || vec![Button::new(6, 3, "A"), Button::new(8, 3, "B")];
+    let dynamic = Screen::new(
+      buttons()
+        .into_iter()
+        .map(|b| Box::new(b) as Box<dyn Draw>)
+        .collect(),
+    );
+    let homogeneous = HomogeneousScreen::new(buttons());
+    let [a, b]: [Button; 2] = buttons().try_into().ok().unwrap();
+    let tuple = TupleScreen::new((a, b));
+
+    assert_eq!(dynamic.draw().to_string(), homogeneous.draw().to_string());
+    assert_eq!(dynamic.draw().to_string(), tuple.draw().to_string());
+    assert_eq!(dynamic.layout(), homogeneous.layout());
+    assert_eq!(dynamic.layout(), tuple.layout());
+  }
+
+  #[test]
+  fn tuple_screens_mix_component_types() {
+    let screen = TupleScreen::new((
+      Label::new(5, 1, "Menu"),
+      Row::new()
+        .with_spacing(1)
+        .child(Checkbox::new(8, 1, "Tea"))
+        .child(Button::new(4, 1, "Go").with_style(crate::Style::plain())),
+    ));
+    assert_eq!(Size::new(13, 2), screen.size());
+    assert_eq!("Menu         \n [ ] Tea  Go \n", screen.draw().to_string());
+  }
+}
@@ -0,0 +1,372 @@
+// Snapshot testing for screens: a screen is drawn headless and compared with a golden file
+// that's kept next to the tests. Snapshots come as text, with a `|` after each row so that
+// trailing spaces survive editors, or as SVG, for looking at in a browser.
+//
+// When a screen is supposed to change, run the tests with UPDATE_SNAPSHOTS=1 to have the
+// golden files rewritten instead of compared, and review the changes like any other diff.
+use crate::{Canvas, Screen};
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const UPDATE_VAR: &str = "UPDATE_SNAPSHOTS";
+
+// Cells are this many pixels wide and high in SVG snapshots.
+const CELL_WIDTH: usize = 8;
+const CELL_HEIGHT: usize = 16;
+
+// Diffs show no more than this many of the rows that differ, and only count the others.
+const MAX_DIFF_ROWS: usize = 10;
+
+pub fn to_text(canvas: &Canvas) -> String {
+  let mut text = String::new();
+  for row in rows(canvas) {
+    text.push_str(&row);
+    text.push_str("|\n");
+  }
+  text
+}
+
+// Each run of characters other than spaces becomes a <text> of its own, stretched to exactly
+// the cells it covers, so that the picture doesn't depend on the widths of the font's glyphs.
+pub fn to_svg(canvas: &Canvas) -> String {
+  let width = canvas.width() as usize * CELL_WIDTH;
+  let height = canvas.height() as usize * CELL_HEIGHT;
+  let mut svg = format!(
+    concat!(
+      "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" ",
+      "viewBox=\"0 0 {w} {h}\" font-family=\"monospace\" font-size=\"14\">\n",
+      "<rect width=\"{w}\" height=\"{h}\" fill=\"white\"/>\n",
+    ),
+    w = width,
+    h = height
+  );
+
+  for (y, row) in rows(canvas).iter().enumerate() {
+    let chars: Vec<char> = row.chars().collect();
+    let mut x = 0;
+    while x < chars.len() {
+      if chars[x] == ' ' {
+        x += 1;
+        continue;
+      }
+      let start = x;
+      while x < chars.len() && chars[x] != ' ' {
+        x += 1;
+      }
+      let run: String = chars[start..x].iter().collect();
+      svg.push_str(&format!(
+        "<text x=\"{}\" y=\"{}\" textLength=\"{}\" lengthAdjust=\"spacingAndGlyphs\">{}</text>\n",
+        start * CELL_WIDTH,
+        (y + 1) * CELL_HEIGHT - 4,
+        (x - start) * CELL_WIDTH,
+        escape(&run)
+      ));
+    }
+  }
+  svg.push_str("</svg>\n");
+  svg
+}
+
+fn rows(canvas: &Canvas) -> Vec<String> {
+  (0..canvas.height())
+    .map(|y| {
+      (0..canvas.width())
+        .map(|x| canvas.get(x, y).unwrap_or(' '))
+        .collect()
+    })
+    .collect()
+}
+
+fn escape(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+  Missing { path: PathBuf },
+  Io { path: PathBuf, error: io::Error },
+  Mismatch { path: PathBuf, diff: String },
+}
+
+impl fmt::Display for SnapshotError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      SnapshotError::Missing { path } => write!(
+        f,
+        "there's no snapshot at {}, run with {}=1 to create it",
+        path.display(),
+        UPDATE_VAR
+      ),
+      SnapshotError::Io { path, error } => write!(f, "can't access {}: {}", path.display(), error),
+      SnapshotError::Mismatch { path, diff } => write!(
+        f,
+        "the screen doesn't match {}\n{}run with {}=1 to accept the new screen",
+        path.display(),
+        diff,
+        UPDATE_VAR
+      ),
+    }
+  }
+}
+
+impl Error for SnapshotError {}
+
+// The golden files in one directory.
+pub struct Snapshots {
+  dir: PathBuf,
+  update: bool,
+}
+
+impl Snapshots {
+  // Whether the golden files get updated is up to the UPDATE_SNAPSHOTS environment variable.
+  pub fn new(dir: impl Into<PathBuf>) -> Snapshots {
+    let update = env::var(UPDATE_VAR).is_ok_and(|v| !v.is_empty() && v != "0");
+    Snapshots {
+      dir: dir.into(),
+      update,
+    }
+  }
+
+  pub fn updating(mut self, update: bool) -> Snapshots {
+    self.update = update;
+    self
+  }
+
+  // Compares the screen with the text snapshot `<name>.txt`.
+  pub fn check_text(&self, name: &str, screen: &Screen) -> Result<(), SnapshotError> {
+    let path = self.dir.join(format!("{}.txt", name));
+    self.check(&path, &to_text(&screen.draw()), cell_diff)
+  }
+
+  // Compares the screen with the SVG snapshot `<name>.svg`.
+  pub fn check_svg(&self, name: &str, screen: &Screen) -> Result<(), SnapshotError> {
+    let path = self.dir.join(format!("{}.svg", name));
+    self.check(&path, &to_svg(&screen.draw()), line_diff)
+  }
+
+  pub fn assert_text(&self, name: &str, screen: &Screen) {
+    if let Err(e) = self.check_text(name, screen) {
+      panic!("{}", e);
+    }
+  }
+
+  pub fn assert_svg(&self, name: &str, screen: &Screen) {
+    if let Err(e) = self.check_svg(name, screen) {
+      panic!("{}", e);
+    }
+  }
+
+  fn check(
+    &self,
+    path: &Path,
+    actual: &str,
+    diff: fn(&str, &str) -> String,
+  ) -> Result<(), SnapshotError> {
+    let io_error = |error| SnapshotError::Io {
+      path: path.to_path_buf(),
+      error,
+    };
+    let expected = match fs::read_to_string(path) {
+      Ok(expected) => Some(expected),
+      Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+      Err(e) => return Err(io_error(e)),
+    };
+    if expected.as_deref() == Some(actual) {
+      return Ok(());
+    }
+
+    match expected {
+      _ if self.update => {
+        fs::create_dir_all(&self.dir).map_err(io_error)?;
+        fs::write(path, actual).map_err(io_error)
+      }
+      None => Err(SnapshotError::Missing {
+        path: path.to_path_buf(),
+      }),
+      Some(expected) => Err(SnapshotError::Mismatch {
+        path: path.to_path_buf(),
+        diff: diff(&expected, actual),
+      }),
+    }
+  }
+}
+
+// The cells of a text snapshot, row by row.
+fn text_cells(text: &str) -> Vec<Vec<char>> {
+  text
+    .lines()
+    .map(|line| line.strip_suffix('|').unwrap_or(line).chars().collect())
+    .collect()
+}
+
+// Which cells differ between two text snapshots: each differing row is shown as it was and as
+// it is, with a `^` under the cells that changed.
+fn cell_diff(expected: &str, actual: &str) -> String {
+  let (expected, actual) = (text_cells(expected), text_cells(actual));
+  let size = |rows: &[Vec<char>]| {
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    format!("{}x{}", width, rows.len())
+  };
+
+  let mut cells = 0;
+  let mut rows = Vec::new();
+  for y in 0..expected.len().max(actual.len()) {
+    let old = expected.get(y).map(Vec::as_slice).unwrap_or(&[]);
+    let new = actual.get(y).map(Vec::as_slice).unwrap_or(&[]);
+    let markers: String = (0..old.len().max(new.len()))
+      .map(|x| if old.get(x) == new.get(x) { ' ' } else { '^' })
+      .collect();
+    if markers.contains('^') {
+      cells += markers.matches('^').count();
+      rows.push((y, old, new, markers));
+    }
+  }
+
+  let mut diff = format!(
+    "{} cell{} in {} row{} differ",
+    cells,
+    if cells == 1 { "" } else { "s" },
+    rows.len(),
+    if rows.len() == 1 { "" } else { "s" }
+  );
+  if size(&expected) != size(&actual) {
+    diff.push_str(&format!(
+      ", the size went from {} to {}",
+      size(&expected),
+      size(&actual)
+    ));
+  }
+  diff.push('\n');
+  for (y, old, new, markers) in rows.iter().take(MAX_DIFF_ROWS) {
+    diff.push_str(&format!(
+      "row {}:\n  expected |{}|\n  actual   |{}|\n            {}\n",
+      y,
+      old.iter().collect::<String>(),
+      new.iter().collect::<String>(),
+      markers.trim_end()
+    ));
+  }
+  if rows.len() > MAX_DIFF_ROWS {
+    diff.push_str(&format!("and {} more rows\n", rows.len() - MAX_DIFF_ROWS));
+  }
+  diff
+}
+
+// SVG snapshots don't have cells to compare, so their diff shows the first line that differs.
+fn line_diff(expected: &str, actual: &str) -> String {
+  let mut expected_lines = expected.lines();
+  let mut actual_lines = actual.lines();
+  let mut n = 1;
+  loop {
+    match (expected_lines.next(), actual_lines.next()) {
+      (old, new) if old == new && old.is_some() => n += 1,
+      (old, new) => {
+        return format!(
+          "line {}:\n  expected {}\n  actual   {}\n",
+          n,
+          old.unwrap_or("(end of file)"),
+          new.unwrap_or("(end of file)")
+        )
+      }
+    }
+  }
+}
+
+// The golden files of this crate's own tests.
+#[cfg(test)]
+pub(crate) fn golden() -> Snapshots {
+  Snapshots::new(Path::new(env!("CARGO_MANIFEST_DIR")).join("golden/snapshots"))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Button, Label};
+
+  fn screen(label: &str) -> Screen {
+    Screen::new(vec![Box::new(Button::new(8, 3, label))])
+  }
+
+  // A directory of its own for each test, since they run in parallel.
+  fn scratch_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("gui-snapshots-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+  }
+
+  #[test]
+  fn text_snapshots_keep_trailing_spaces() {
+    let screen = Screen::new(vec![
+      Box::new(Label::new(4, 1, "ab")),
+      Box::new(Label::new(2, 1, "<>")),
+    ]);
+    assert_eq!("ab  |\n<>  |\n", to_text(&screen.draw()));
+  }
+
+  #[test]
+  fn svg_snapshots_place_text_on_the_cells() {
+    let screen = Screen::new(vec![Box::new(Label::new(7, 1, "a<b  cd"))]);
+    assert_eq!(
+      concat!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"56\" height=\"16\" ",
+        "viewBox=\"0 0 56 16\" font-family=\"monospace\" font-size=\"14\">\n",
+        "<rect width=\"56\" height=\"16\" fill=\"white\"/>\n",
+        "<text x=\"0\" y=\"12\" textLength=\"24\" lengthAdjust=\"spacingAndGlyphs\">a&lt;b</text>\n",
+        "<text x=\"40\" y=\"12\" textLength=\"16\" lengthAdjust=\"spacingAndGlyphs\">cd</text>\n",
+        "</svg>\n",
+      ),
+      to_svg(&screen.draw())
+    );
+  }
+
+  #[test]
+  fn mismatches_point_at_the_cells_that_changed() {
+    let diff = cell_diff("┌──┐|\n│OK│|\n└──┘|\n", "┌──┐|\n│Ok│|\n└──┘|\n");
+    assert_eq!(
+      concat!(
+        "1 cell in 1 row differ\n",
+        "row 1:\n",
+        "  expected |│OK│|\n",
+        "  actual   |│Ok│|\n",
+        "              ^\n",
+      ),
+      diff
+    );
+
+    let diff = cell_diff("ab|\n", "ab |\ncd |\n");
+    assert!(diff.starts_with("4 cells in 2 rows differ, the size went from 2x1 to 3x2\n"));
+  }
+
+  #[test]
+  fn update_mode_writes_the_golden_files() {
+    let dir = scratch_dir("update");
+    let snapshots = Snapshots::new(&dir).updating(false);
+    assert!(matches!(
+      snapshots.check_text("ok", &screen("OK")),
+      Err(SnapshotError::Missing { .. })
+    ));
+
+    let updating = Snapshots::new(&dir).updating(true);
+    updating.check_text("ok", &screen("OK")).unwrap();
+    updating.check_svg("ok", &screen("OK")).unwrap();
+    snapshots.check_text("ok", &screen("OK")).unwrap();
+    snapshots.check_svg("ok", &screen("OK")).unwrap();
+
+    let error = snapshots.check_text("ok", &screen("KO")).unwrap_err();
+    assert!(matches!(error, SnapshotError::Mismatch { .. }));
+    assert!(error.to_string().contains("2 cells in 1 row differ"));
+    let error = snapshots.check_svg("ok", &screen("KO")).unwrap_err();
+    assert!(error.to_string().contains("line 5:"));
+
+    updating.check_text("ok", &screen("KO")).unwrap();
+    snapshots.check_text("ok", &screen("KO")).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+  }
+}
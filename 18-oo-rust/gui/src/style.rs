@@ -0,0 +1,351 @@
+// How components look. A Style only says what it wants to change, and leaves the rest to the
+// styles it's laid over: the screen's Theme has a style for each kind of component, containers
+// may lay a theme of their own over it for their children, and each widget's own style goes
+// over whatever theme it ends up with. What's still unset after all that gets a default.
+//
+// Each of those comes in variants for the states a widget can be in, which are laid over its
+// normal style: hovered, then focused, then disabled, for the states the widget is in.
+use crate::{Align, Border, Canvas, Color, Padding, Rect, Size};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+  pub border: Option<Border>,
+  pub padding: Option<Padding>,
+  pub fg: Option<Color>,
+  pub bg: Option<Color>,
+  pub align: Option<Align>,
+}
+
+impl Style {
+  pub fn plain() -> Style {
+    Style::default().with_border(Border::None)
+  }
+
+  pub fn bordered() -> Style {
+    Style::default().with_border(Border::Single)
+  }
+
+  pub fn with_border(mut self, border: Border) -> Style {
+    self.border = Some(border);
+    self
+  }
+
+  pub fn with_padding(mut self, padding: Padding) -> Style {
+    self.padding = Some(padding);
+    self
+  }
+
+  pub fn with_fg(mut self, fg: Color) -> Style {
+    self.fg = Some(fg);
+    self
+  }
+
+  pub fn with_bg(mut self, bg: Color) -> Style {
+    self.bg = Some(bg);
+    self
+  }
+
+  pub fn with_align(mut self, align: Align) -> Style {
+    self.align = Some(align);
+    self
+  }
+
+  // This style laid over `base`: what's set here wins, the rest comes from `base`.
+  pub fn over(&self, base: &Style) -> Style {
+    Style {
+      border: self.border.or(base.border),
+      padding: self.padding.or(base.padding),
+      fg: self.fg.or(base.fg),
+      bg: self.bg.or(base.bg),
+      align: self.align.or(base.align),
+    }
+  }
+
+  pub fn computed(&self) -> ComputedStyle {
+    ComputedStyle {
+      border: self.border.unwrap_or(Border::None),
+      padding: self.padding.unwrap_or_default(),
+      fg: self.fg.unwrap_or_default(),
+      bg: self.bg.unwrap_or_default(),
+      align: self.align.unwrap_or_default(),
+    }
+  }
+}
+
+// A style with everything settled, which is what widgets draw with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ComputedStyle {
+  pub border: Border,
+  pub padding: Padding,
+  pub fg: Color,
+  pub bg: Color,
+  pub align: Align,
+}
+
+impl ComputedStyle {
+  // The part of a widget of `size` that's left for its content.
+  pub fn content_rect(&self, size: Size) -> Rect {
+    let border = self.border.width();
+    let left = border.saturating_add(self.padding.left);
+    let top = border.saturating_add(self.padding.top);
+    let horizontal = (2 * border).saturating_add(self.padding.horizontal());
+    let vertical = (2 * border).saturating_add(self.padding.vertical());
+    Rect::new(
+      left,
+      top,
+      size.width.saturating_sub(horizontal),
+      size.height.saturating_sub(vertical),
+    )
+  }
+
+  // Runs `f` with the style's colors, after filling the widget with its background color if it
+  // has one.
+  pub fn paint<R>(&self, canvas: &mut Canvas, size: Size, f: impl FnOnce(&mut Canvas) -> R) -> R {
+    canvas.painted(self.fg, self.bg, |canvas| {
+      if self.bg != Color::Default {
+        canvas.fill(Rect::new(0, 0, size.width, size.height), ' ');
+      }
+      f(canvas)
+    })
+  }
+
+  // Draws the border, which is doubled for the focused widget.
+  pub fn draw_frame(&self, canvas: &mut Canvas, size: Size, focused: bool) {
+    let border = match self.border {
+      Border::Single if focused => Border::Double,
+      border => border,
+    };
+    canvas.border_with(Rect::new(0, 0, size.width, size.height), border);
+  }
+}
+
+// The states a widget can be in, which may all be true at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WidgetState {
+  pub hovered: bool,
+  pub focused: bool,
+  pub disabled: bool,
+}
+
+// A style for each state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StateStyles {
+  pub normal: Style,
+  pub hovered: Style,
+  pub focused: Style,
+  pub disabled: Style,
+}
+
+impl StateStyles {
+  pub fn new(normal: Style) -> StateStyles {
+    StateStyles {
+      normal,
+      ..StateStyles::default()
+    }
+  }
+
+  pub fn hovered(mut self, style: Style) -> StateStyles {
+    self.hovered = style;
+    self
+  }
+
+  pub fn focused(mut self, style: Style) -> StateStyles {
+    self.focused = style;
+    self
+  }
+
+  pub fn disabled(mut self, style: Style) -> StateStyles {
+    self.disabled = style;
+    self
+  }
+
+  pub fn over(&self, base: &StateStyles) -> StateStyles {
+    StateStyles {
+      normal: self.normal.over(&base.normal),
+      hovered: self.hovered.over(&base.hovered),
+      focused: self.focused.over(&base.focused),
+      disabled: self.disabled.over(&base.disabled),
+    }
+  }
+
+  // The style for a widget in `state`.
+  pub fn for_state(&self, state: WidgetState) -> Style {
+    let mut style = self.normal;
+    for (active, variant) in [
+      (state.hovered, &self.hovered),
+      (state.focused, &self.focused),
+      (state.disabled, &self.disabled),
+    ] {
+      if active {
+        style = variant.over(&style);
+      }
+    }
+    style
+  }
+}
+
+// Styles by kind of component, which is the name of its type, as in "Button". The base styles
+// apply to every kind, under the styles of the kind itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+  pub base: StateStyles,
+  pub components: HashMap<String, StateStyles>,
+}
+
+impl Default for Theme {
+  fn default() -> Theme {
+    Theme::standard()
+  }
+}
+
+impl Theme {
+  // A theme that doesn't change anything, for containers to start theirs from.
+  pub fn empty() -> Theme {
+    Theme {
+      base: StateStyles::default(),
+      components: HashMap::new(),
+    }
+  }
+
+  // The look of the widgets when nobody asks for anything else.
+  pub fn standard() -> Theme {
+    let mut theme = Theme::empty();
+    for kind in [
+      "Button",
+      "Label",
+      "Checkbox",
+      "TextInput",
+      "SelectBox",
+      "ListView",
+    ] {
+      theme = theme.with(kind, standard_styles(kind));
+    }
+    theme
+  }
+
+  // White on black, with the focused widget in yellow and the disabled ones faded to blue.
+  pub fn dark() -> Theme {
+    let base = StateStyles::new(Style::default().with_fg(Color::White).with_bg(Color::Black))
+      .hovered(Style::default().with_fg(Color::Cyan))
+      .focused(Style::default().with_fg(Color::Yellow))
+      .disabled(Style::default().with_fg(Color::Blue));
+    Theme {
+      base,
+      ..Theme::standard()
+    }
+  }
+
+  pub fn with_base(mut self, styles: StateStyles) -> Theme {
+    self.base = styles;
+    self
+  }
+
+  pub fn with(mut self, kind: &str, styles: StateStyles) -> Theme {
+    self.components.insert(kind.to_string(), styles);
+    self
+  }
+
+  // This theme laid over `base`, kind by kind.
+  pub fn over(&self, base: &Theme) -> Theme {
+    let mut components = base.components.clone();
+    for (kind, styles) in self.components.iter() {
+      let merged = match base.components.get(kind) {
+        Some(base) => styles.over(base),
+        None => *styles,
+      };
+      components.insert(kind.clone(), merged);
+    }
+    Theme {
+      base: self.base.over(&base.base),
+      components,
+    }
+  }
+
+  pub fn styles_for(&self, kind: &str) -> StateStyles {
+    match self.components.get(kind) {
+      Some(styles) => styles.over(&self.base),
+      None => self.base,
+    }
+  }
+}
+
+// What the standard theme has for a kind of widget, without building the whole theme: widgets
+// start out with these until they're given a theme.
+pub(crate) fn standard_styles(kind: &str) -> StateStyles {
+  match kind {
+    "Button" => StateStyles::new(Style::bordered().with_align(Align::Center)),
+    "TextInput" | "SelectBox" | "ListView" => StateStyles::new(Style::bordered()),
+    _ => StateStyles::new(Style::plain()),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn content_rect_leaves_room_for_border_and_padding() {
+    let style = Style::bordered().with_padding(Padding {
+      left: 2,
+      ..Padding::default()
+    });
+    assert_eq!(
+      Rect::new(3, 1, 6, 1),
+      style.computed().content_rect(Size::new(10, 3))
+    );
+    assert_eq!(
+      Rect::new(0, 0, 4, 1),
+      Style::plain().computed().content_rect(Size::new(4, 1))
+    );
+  }
+
+  #[test]
+  fn state_variants_are_laid_over_the_normal_style() {
+    let styles = StateStyles::new(Style::bordered().with_fg(Color::White))
+      .hovered(Style::default().with_fg(Color::Cyan).with_bg(Color::Black))
+      .focused(Style::default().with_fg(Color::Yellow));
+
+    let state = |hovered, focused| WidgetState {
+      hovered,
+      focused,
+      disabled: false,
+    };
+    assert_eq!(Some(Color::White), styles.for_state(state(false, false)).fg);
+    let hovered = styles.for_state(state(true, false));
+    assert_eq!(
+      (Some(Color::Cyan), Some(Color::Black)),
+      (hovered.fg, hovered.bg)
+    );
+    // focus wins over hovering, but only for what it sets
+    let both = styles.for_state(state(true, true));
+    assert_eq!(
+      (Some(Color::Yellow), Some(Color::Black)),
+      (both.fg, both.bg)
+    );
+    assert_eq!(Some(Border::Single), both.border);
+  }
+
+  #[test]
+  fn themes_cascade_kind_by_kind() {
+    let screen =
+      Theme::standard().with_base(StateStyles::new(Style::default().with_fg(Color::Green)));
+    let container = Theme::empty()
+      .with(
+        "Button",
+        StateStyles::new(Style::default().with_fg(Color::Red)),
+      )
+      .with("Gauge", StateStyles::new(Style::bordered()));
+    let theme = container.over(&screen);
+
+    let button = theme.styles_for("Button").normal.computed();
+    assert_eq!((Border::Single, Color::Red), (button.border, button.fg));
+    assert_eq!(Align::Center, button.align);
+    let label = theme.styles_for("Label").normal.computed();
+    assert_eq!((Border::None, Color::Green), (label.border, label.fg));
+    assert_eq!(
+      Border::Single,
+      theme.styles_for("Gauge").normal.computed().border
+    );
+  }
+}
@@ -0,0 +1,149 @@
+use super::impl_widget;
+use crate::style::{standard_styles, StateStyles};
+use crate::{
+  Accessible, AccessibleState, Canvas, Draw, Event, Interactive, Key, Role, Size, Style, Theme,
+  WidgetState,
+};
+
+pub struct Button {
+  pub width: u16,
+  pub height: u16,
+  pub label: String,
+  pub style: Style,
+  pub accessible_name: Option<String>,
+  dirty: bool,
+  state: WidgetState,
+  // what the theme has for buttons
+  themed: StateStyles,
+  on_click: Option<Box<dyn FnMut()>>,
+}
+
+impl_widget!(Button);
+
+impl Button {
+  pub fn new(width: u16, height: u16, label: &str) -> Button {
+    Button {
+      width,
+      height,
+      label: label.to_string(),
+      style: Style::default(),
+      accessible_name: None,
+      dirty: true,
+      state: WidgetState::default(),
+      themed: standard_styles("Button"),
+      on_click: None,
+    }
+  }
+
+  // Registers the callback run whenever the button is clicked or Enter/Space is pressed on it.
+  pub fn on_click(mut self, callback: impl FnMut() + 'static) -> Button {
+    self.on_click = Some(Box::new(callback));
+    self
+  }
+
+  pub fn click(&mut self) {
+    if let Some(callback) = self.on_click.as_mut() {
+      callback();
+    }
+  }
+}
+
+impl Draw for Button {
+  fn size(&self) -> Size {
+    Size::new(self.width, self.height)
+  }
+
+  fn is_dirty(&self) -> bool {
+    self.dirty
+  }
+
+  fn mark_clean(&mut self) {
+    self.dirty = false;
+  }
+
+  fn restyle(&mut self, theme: &Theme) {
+    self.restyle_with(theme);
+  }
+
+  fn as_accessible(&self) -> Option<&dyn Accessible> {
+    Some(self)
+  }
+
+  fn draw(&self, canvas: &mut Canvas) {
+    let style = self.computed_style();
+    style.paint(canvas, self.size(), |canvas| {
+      style.draw_frame(canvas, self.size(), self.state.focused);
+      let content = self.content_rect();
+      if content.height > 0 {
+        canvas.aligned(content, (content.height - 1) / 2, &self.label, style.align);
+      }
+    });
+  }
+
+  fn as_interactive(&mut self) -> Option<&mut dyn Interactive> {
+    Some(self)
+  }
+}
+
+impl Interactive for Button {
+  fn handle_event(&mut self, event: &Event) -> bool {
+    match event {
+      Event::Click { .. } | Event::Key(Key::Enter | Key::Space) => {
+        self.click();
+        true
+      }
+      _ => false,
+    }
+  }
+
+  fn set_focused(&mut self, focused: bool) {
+    self.set_state(WidgetState {
+      focused,
+      ..self.state
+    });
+  }
+
+  fn set_hovered(&mut self, hovered: bool) {
+    self.set_state(WidgetState {
+      hovered,
+      ..self.state
+    });
+  }
+
+  fn is_disabled(&self) -> bool {
+    self.state.disabled
+  }
+}
+
+impl Accessible for Button {
+  fn role(&self) -> Role {
+    Role::Button
+  }
+
+  fn name(&self) -> Option<&str> {
+    Some(self.accessible_name.as_deref().unwrap_or(&self.label))
+  }
+
+  fn state(&self) -> AccessibleState {
+    self.accessible_state()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::snapshot::golden;
+  use crate::Screen;
+
+  #[test]
+  fn button_snapshots() {
+    let mut screen = Screen::new(vec![
+      Box::new(Button::new(10, 3, "OK")),
+      Box::new(Button::new(10, 3, "Cancel")),
+      Box::new(Button::new(10, 1, "Plain").with_style(Style::plain())),
+    ]);
+    screen.handle_event(Event::FocusNext);
+    golden().assert_text("button", &screen);
+    golden().assert_svg("button", &screen);
+  }
+}
@@ -0,0 +1,170 @@
+use super::impl_widget;
+use crate::style::{standard_styles, StateStyles};
+use crate::{
+  Accessible, AccessibleState, Canvas, Draw, Event, Interactive, Key, Role, Size, Style, Theme,
+  WidgetState,
+};
+
+pub struct Checkbox {
+  pub width: u16,
+  pub height: u16,
+  pub label: String,
+  pub checked: bool,
+  pub style: Style,
+  pub accessible_name: Option<String>,
+  dirty: bool,
+  state: WidgetState,
+  // what the theme has for checkboxes
+  themed: StateStyles,
+  on_toggle: Option<Box<dyn FnMut(bool)>>,
+}
+
+impl_widget!(Checkbox);
+
+impl Checkbox {
+  pub fn new(width: u16, height: u16, label: &str) -> Checkbox {
+    Checkbox {
+      width,
+      height,
+      label: label.to_string(),
+      checked: false,
+      style: Style::default(),
+      accessible_name: None,
+      dirty: true,
+      state: WidgetState::default(),
+      themed: standard_styles("Checkbox"),
+      on_toggle: None,
+    }
+  }
+
+  // Registers the callback run with the new state whenever the checkbox is toggled.
+  pub fn on_toggle(mut self, callback: impl FnMut(bool) + 'static) -> Checkbox {
+    self.on_toggle = Some(Box::new(callback));
+    self
+  }
+
+  pub fn toggle(&mut self) {
+    self.checked = !self.checked;
+    self.dirty = true;
+    if let Some(callback) = self.on_toggle.as_mut() {
+      callback(self.checked);
+    }
+  }
+}
+
+impl Draw for Checkbox {
+  fn size(&self) -> Size {
+    Size::new(self.width, self.height)
+  }
+
+  fn is_dirty(&self) -> bool {
+    self.dirty
+  }
+
+  fn mark_clean(&mut self) {
+    self.dirty = false;
+  }
+
+  fn restyle(&mut self, theme: &Theme) {
+    self.restyle_with(theme);
+  }
+
+  fn as_accessible(&self) -> Option<&dyn Accessible> {
+    Some(self)
+  }
+
+  fn draw(&self, canvas: &mut Canvas) {
+    let style = self.computed_style();
+    style.paint(canvas, self.size(), |canvas| {
+      style.draw_frame(canvas, self.size(), self.state.focused);
+      let mark = if self.checked { 'x' } else { ' ' };
+      // Without a border to double, the focus shows as a `>` in front of the box.
+      let pointer = if self.state.focused { '>' } else { ' ' };
+      canvas.within(self.content_rect(), |canvas| {
+        canvas.text(0, 0, &format!("{}[{}] {}", pointer, mark, self.label));
+      });
+    });
+  }
+
+  fn as_interactive(&mut self) -> Option<&mut dyn Interactive> {
+    Some(self)
+  }
+}
+
+impl Interactive for Checkbox {
+  fn handle_event(&mut self, event: &Event) -> bool {
+    match event {
+      Event::Click { .. } | Event::Key(Key::Enter | Key::Space) => {
+        self.toggle();
+        true
+      }
+      _ => false,
+    }
+  }
+
+  fn set_focused(&mut self, focused: bool) {
+    self.set_state(WidgetState {
+      focused,
+      ..self.state
+    });
+  }
+
+  fn set_hovered(&mut self, hovered: bool) {
+    self.set_state(WidgetState {
+      hovered,
+      ..self.state
+    });
+  }
+
+  fn is_disabled(&self) -> bool {
+    self.state.disabled
+  }
+}
+
+impl Accessible for Checkbox {
+  fn role(&self) -> Role {
+    Role::Checkbox
+  }
+
+  fn name(&self) -> Option<&str> {
+    Some(self.accessible_name.as_deref().unwrap_or(&self.label))
+  }
+
+  fn state(&self) -> AccessibleState {
+    AccessibleState {
+      checked: Some(self.checked),
+      ..self.accessible_state()
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::snapshot::golden;
+  use crate::Screen;
+
+  #[test]
+  fn space_toggles_the_checkbox() {
+    let mut checkbox = Checkbox::new(12, 1, "Salad");
+    checkbox.set_focused(true);
+    assert!(checkbox.handle_event(&Event::Key(Key::Space)));
+
+    let mut canvas = Canvas::new(12, 1);
+    checkbox.draw(&mut canvas);
+    assert_eq!(">[x] Salad  \n", canvas.to_string());
+  }
+
+  #[test]
+  fn checkbox_snapshots() {
+    let mut checked = Checkbox::new(12, 1, "Salad");
+    checked.checked = true;
+    let mut screen = Screen::new(vec![
+      Box::new(Checkbox::new(12, 1, "Soup")),
+      Box::new(checked),
+      Box::new(Checkbox::new(12, 3, "Dessert").with_style(Style::bordered())),
+    ]);
+    screen.handle_event(Event::FocusNext);
+    golden().assert_text("checkbox", &screen);
+  }
+}
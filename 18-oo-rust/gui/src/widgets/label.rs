@@ -0,0 +1,103 @@
+use super::impl_widget;
+use crate::style::{standard_styles, StateStyles};
+use crate::{Accessible, AccessibleState, Canvas, Draw, Role, Size, Style, Theme, WidgetState};
+
+// Text that's just there to be read, one line per row.
+pub struct Label {
+  pub width: u16,
+  pub height: u16,
+  pub text: String,
+  pub style: Style,
+  pub accessible_name: Option<String>,
+  dirty: bool,
+  state: WidgetState,
+  // what the theme has for labels
+  themed: StateStyles,
+}
+
+impl_widget!(Label);
+
+impl Label {
+  pub fn new(width: u16, height: u16, text: &str) -> Label {
+    Label {
+      width,
+      height,
+      text: text.to_string(),
+      style: Style::default(),
+      accessible_name: None,
+      dirty: true,
+      state: WidgetState::default(),
+      themed: standard_styles("Label"),
+    }
+  }
+}
+
+impl Draw for Label {
+  fn size(&self) -> Size {
+    Size::new(self.width, self.height)
+  }
+
+  fn is_dirty(&self) -> bool {
+    self.dirty
+  }
+
+  fn mark_clean(&mut self) {
+    self.dirty = false;
+  }
+
+  fn restyle(&mut self, theme: &Theme) {
+    self.restyle_with(theme);
+  }
+
+  fn as_accessible(&self) -> Option<&dyn Accessible> {
+    Some(self)
+  }
+
+  fn draw(&self, canvas: &mut Canvas) {
+    let style = self.computed_style();
+    style.paint(canvas, self.size(), |canvas| {
+      style.draw_frame(canvas, self.size(), false);
+      let content = self.content_rect();
+      for (y, line) in self.text.lines().enumerate().take(content.height as usize) {
+        canvas.aligned(content, y as u16, line, style.align);
+      }
+    });
+  }
+}
+
+impl Accessible for Label {
+  fn role(&self) -> Role {
+    Role::Label
+  }
+
+  fn name(&self) -> Option<&str> {
+    Some(self.accessible_name.as_deref().unwrap_or(&self.text))
+  }
+
+  fn state(&self) -> AccessibleState {
+    self.accessible_state()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::snapshot::golden;
+  use crate::Screen;
+
+  #[test]
+  fn lines_are_clipped_to_the_label() {
+    let mut canvas = Canvas::new(5, 3);
+    Label::new(4, 2, "Hello\nsalad\nlunch").draw(&mut canvas);
+    assert_eq!("Hell \nsala \n     \n", canvas.to_string());
+  }
+
+  #[test]
+  fn label_snapshots() {
+    let screen = Screen::new(vec![
+      Box::new(Label::new(12, 2, "Name:\nAddress:")),
+      Box::new(Label::new(12, 3, "boxed").with_style(Style::bordered())),
+    ]);
+    golden().assert_text("label", &screen);
+  }
+}
@@ -0,0 +1,254 @@
+use super::{impl_widget, scroll_to};
+use crate::style::{standard_styles, StateStyles};
+use crate::{
+  Accessible, AccessibleState, Canvas, Draw, Event, Interactive, Key, Role, Size, Style, Theme,
+  WidgetState,
+};
+
+// Called with the index and the text of the newly selected item.
+type SelectCallback = Box<dyn FnMut(usize, &str)>;
+
+// A scrollable list of items, of which one may be selected. Arrows on the right edge show that
+// there are more items above or below the ones in view.
+pub struct ListView {
+  pub width: u16,
+  pub height: u16,
+  pub items: Vec<String>,
+  pub style: Style,
+  pub accessible_name: Option<String>,
+  dirty: bool,
+  selected: Option<usize>,
+  offset: usize,
+  state: WidgetState,
+  // what the theme has for list views
+  themed: StateStyles,
+  on_select: Option<SelectCallback>,
+}
+
+impl_widget!(ListView);
+
+impl ListView {
+  pub fn new(width: u16, height: u16, items: Vec<String>) -> ListView {
+    ListView {
+      width,
+      height,
+      items,
+      style: Style::default(),
+      accessible_name: None,
+      dirty: true,
+      selected: None,
+      offset: 0,
+      state: WidgetState::default(),
+      themed: standard_styles("ListView"),
+      on_select: None,
+    }
+  }
+
+  pub fn on_select(mut self, callback: impl FnMut(usize, &str) + 'static) -> ListView {
+    self.on_select = Some(Box::new(callback));
+    self
+  }
+
+  pub fn selected(&self) -> Option<usize> {
+    self.selected
+  }
+
+  // The index of the first item in view.
+  pub fn offset(&self) -> usize {
+    self.offset
+  }
+
+  fn visible_rows(&self) -> usize {
+    self.content_rect().height as usize
+  }
+
+  // Scrolls the list without changing the selection, stopping at either end.
+  pub fn scroll_by(&mut self, rows: isize) {
+    let max = self.items.len().saturating_sub(self.visible_rows());
+    let offset = self.offset.saturating_add_signed(rows).min(max);
+    self.dirty |= offset != self.offset;
+    self.offset = offset;
+  }
+
+  // Selects the item at `index` and scrolls it into view.
+  pub fn select(&mut self, index: usize) -> bool {
+    if index >= self.items.len() || Some(index) == self.selected {
+      return false;
+    }
+    self.selected = Some(index);
+    self.offset = scroll_to(self.offset, self.visible_rows(), index);
+    self.dirty = true;
+    if let Some(callback) = self.on_select.as_mut() {
+      callback(index, &self.items[index]);
+    }
+    true
+  }
+}
+
+impl Draw for ListView {
+  fn size(&self) -> Size {
+    Size::new(self.width, self.height)
+  }
+
+  fn is_dirty(&self) -> bool {
+    self.dirty
+  }
+
+  fn mark_clean(&mut self) {
+    self.dirty = false;
+  }
+
+  fn restyle(&mut self, theme: &Theme) {
+    self.restyle_with(theme);
+  }
+
+  fn as_accessible(&self) -> Option<&dyn Accessible> {
+    Some(self)
+  }
+
+  fn draw(&self, canvas: &mut Canvas) {
+    let style = self.computed_style();
+    style.paint(canvas, self.size(), |canvas| {
+      style.draw_frame(canvas, self.size(), self.state.focused);
+      let content = self.content_rect();
+      let rows = self.visible_rows();
+      canvas.within(content, |canvas| {
+        let shown = self.items.iter().enumerate().skip(self.offset);
+        for (y, (i, item)) in shown.take(rows).enumerate() {
+          if Some(i) == self.selected {
+            canvas.put(0, y as u16, '>');
+          }
+          canvas.text(1, y as u16, item);
+        }
+
+        let last = content.width.saturating_sub(1);
+        if self.offset > 0 {
+          canvas.put(last, 0, '▲');
+        }
+        if self.offset + rows < self.items.len() {
+          canvas.put(last, content.height.saturating_sub(1), '▼');
+        }
+      });
+    });
+  }
+
+  fn as_interactive(&mut self) -> Option<&mut dyn Interactive> {
+    Some(self)
+  }
+}
+
+impl Interactive for ListView {
+  fn handle_event(&mut self, event: &Event) -> bool {
+    let content = self.content_rect();
+    match (event, self.selected) {
+      (Event::Click { y, .. }, _) if *y >= content.y && *y < content.bottom() => {
+        self.select(self.offset + (*y - content.y) as usize)
+      }
+      (Event::Key(Key::Down), None) | (Event::Key(Key::Home), _) => self.select(0),
+      (Event::Key(Key::Down), Some(i)) => self.select(i + 1),
+      (Event::Key(Key::Up), Some(i)) if i > 0 => self.select(i - 1),
+      (Event::Key(Key::End), _) => self.select(self.items.len().saturating_sub(1)),
+      _ => false,
+    }
+  }
+
+  fn set_focused(&mut self, focused: bool) {
+    self.set_state(WidgetState {
+      focused,
+      ..self.state
+    });
+  }
+
+  fn set_hovered(&mut self, hovered: bool) {
+    self.set_state(WidgetState {
+      hovered,
+      ..self.state
+    });
+  }
+
+  fn is_disabled(&self) -> bool {
+    self.state.disabled
+  }
+}
+
+impl Accessible for ListView {
+  fn role(&self) -> Role {
+    Role::ListBox
+  }
+
+  fn name(&self) -> Option<&str> {
+    self.accessible_name.as_deref()
+  }
+
+  fn value(&self) -> Option<String> {
+    // the items are there for anyone to change, selected or not
+    self.selected.and_then(|i| self.items.get(i)).cloned()
+  }
+
+  fn state(&self) -> AccessibleState {
+    self.accessible_state()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::snapshot::golden;
+  use crate::Screen;
+
+  fn list() -> ListView {
+    let items = (1..=10).map(|n| format!("item {}", n)).collect();
+    ListView::new(10, 5, items)
+  }
+
+  #[test]
+  fn scrolling_stops_at_the_last_page() {
+    let mut list = list();
+    list.scroll_by(4);
+    assert_eq!(4, list.offset());
+    list.scroll_by(100);
+    assert_eq!(7, list.offset());
+    list.scroll_by(-100);
+    assert_eq!(0, list.offset());
+  }
+
+  #[test]
+  fn selection_scrolls_into_view() {
+    let mut list = list();
+    for _ in 0..5 {
+      list.handle_event(&Event::Key(Key::Down));
+    }
+    assert_eq!(Some(4), list.selected());
+
+    let mut canvas = Canvas::new(10, 5);
+    list.draw(&mut canvas);
+    assert_eq!(
+      concat!(
+        "┌────────┐\n",
+        "│ item 3▲│\n",
+        "│ item 4 │\n",
+        "│>item 5▼│\n",
+        "└────────┘\n",
+      ),
+      canvas.to_string()
+    );
+  }
+
+  #[test]
+  fn selection_outlives_the_items() {
+    let mut list = list();
+    list.select(2);
+    list.items.clear();
+    let screen = Screen::new(vec![Box::new(list)]);
+    assert_eq!(None, screen.accessibility_tree().children[0].value);
+  }
+
+  #[test]
+  fn list_view_snapshots() {
+    let mut scrolled = list();
+    scrolled.select(6);
+    let mut screen = Screen::new(vec![Box::new(list()), Box::new(scrolled)]);
+    screen.handle_event(Event::FocusNext);
+    golden().assert_text("list_view", &screen);
+  }
+}
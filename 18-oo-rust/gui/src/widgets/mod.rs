@@ -0,0 +1,111 @@
+// The widgets all follow the same model: a `width` and a `height` for the room they'd like to get,
+// and a style deciding how much of that room goes to a border and padding. The rest is the
+// content rect, where each widget draws its own content. The style a widget draws with is its
+// own Style laid over what the theme has for its kind and its current state.
+mod button;
+mod checkbox;
+mod label;
+mod list_view;
+mod select_box;
+mod text_input;
+
+pub use button::Button;
+pub use checkbox::Checkbox;
+pub use label::Label;
+pub use list_view::ListView;
+pub use select_box::SelectBox;
+pub use text_input::TextInput;
+
+// What every widget has: its size, a style that can be replaced, its state, an accessible name
+// and a dirty flag.
+macro_rules! impl_widget {
+  ($($widget:ident),*) => {
+    $(
+      impl $widget {
+        pub fn with_style(mut self, style: crate::Style) -> $widget {
+          self.style = style;
+          self
+        }
+
+        // The name screen readers use instead of the one the widget would give itself.
+        pub fn with_accessible_name(mut self, name: &str) -> $widget {
+          self.accessible_name = Some(name.to_string());
+          self
+        }
+
+        // Widgets keep track of their own changes, but not of changes made to their public
+        // fields: whoever changes those has to tell the widget to redraw itself.
+        pub fn mark_dirty(&mut self) {
+          self.dirty = true;
+        }
+
+        pub fn is_disabled(&self) -> bool {
+          self.state.disabled
+        }
+
+        // Disabled widgets are drawn in their disabled style, and get neither focus nor events.
+        pub fn set_disabled(&mut self, disabled: bool) {
+          self.set_state(crate::WidgetState {
+            disabled,
+            ..self.state
+          });
+        }
+
+        fn set_state(&mut self, state: crate::WidgetState) {
+          self.dirty |= state != self.state;
+          self.state = state;
+        }
+
+        // The style the widget draws with, as things are.
+        pub fn computed_style(&self) -> crate::ComputedStyle {
+          self.style.over(&self.themed.for_state(self.state)).computed()
+        }
+
+        fn content_rect(&self) -> crate::Rect {
+          self
+            .computed_style()
+            .content_rect(crate::Size::new(self.width, self.height))
+        }
+
+        fn accessible_state(&self) -> crate::AccessibleState {
+          crate::AccessibleState {
+            focused: self.state.focused,
+            disabled: self.state.disabled,
+            checked: None,
+          }
+        }
+
+        fn restyle_with(&mut self, theme: &crate::Theme) {
+          let themed = theme.styles_for(stringify!($widget));
+          self.dirty |= themed != self.themed;
+          self.themed = themed;
+        }
+      }
+    )*
+  };
+}
+
+pub(crate) use impl_widget;
+
+// The first `len` items starting at `offset`, shifted so that `index` is among them.
+fn scroll_to(offset: usize, len: usize, index: usize) -> usize {
+  if index < offset {
+    index
+  } else if len > 0 && index >= offset + len {
+    index + 1 - len
+  } else {
+    offset
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn scrolling_keeps_the_index_in_view() {
+    assert_eq!(0, scroll_to(0, 3, 2));
+    assert_eq!(1, scroll_to(0, 3, 3));
+    assert_eq!(2, scroll_to(4, 3, 2));
+  }
+}
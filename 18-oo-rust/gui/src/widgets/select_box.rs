@@ -0,0 +1,234 @@
+use super::{impl_widget, scroll_to};
+use crate::style::{standard_styles, StateStyles};
+use crate::{
+  Accessible, AccessibleState, Canvas, Draw, Event, Interactive, Key, Role, Size, Style, Theme,
+  WidgetState,
+};
+
+// Called with the index and the text of the newly selected option.
+type ChangeCallback = Box<dyn FnMut(usize, &str)>;
+
+// A list of options of which exactly one is selected.
+pub struct SelectBox {
+  pub width: u16,
+  pub height: u16,
+  pub options: Vec<String>,
+  pub style: Style,
+  pub accessible_name: Option<String>,
+  dirty: bool,
+  selected: usize,
+  state: WidgetState,
+  // what the theme has for select boxes
+  themed: StateStyles,
+  on_change: Option<ChangeCallback>,
+}
+
+impl_widget!(SelectBox);
+
+impl SelectBox {
+  pub fn new(width: u16, height: u16, options: Vec<String>) -> SelectBox {
+    SelectBox {
+      width,
+      height,
+      options,
+      style: Style::default(),
+      accessible_name: None,
+      dirty: true,
+      selected: 0,
+      state: WidgetState::default(),
+      themed: standard_styles("SelectBox"),
+      on_change: None,
+    }
+  }
+
+  pub fn on_change(mut self, callback: impl FnMut(usize, &str) + 'static) -> SelectBox {
+    self.on_change = Some(Box::new(callback));
+    self
+  }
+
+  pub fn selected(&self) -> usize {
+    self.selected
+  }
+
+  pub fn selected_option(&self) -> Option<&str> {
+    self.options.get(self.selected).map(|o| o.as_str())
+  }
+
+  // Selects the option at `index`, returning whether that changed the selection.
+  pub fn select(&mut self, index: usize) -> bool {
+    if index >= self.options.len() || index == self.selected {
+      return false;
+    }
+    self.selected = index;
+    self.dirty = true;
+    if let Some(callback) = self.on_change.as_mut() {
+      callback(index, &self.options[index]);
+    }
+    true
+  }
+
+  // The first option shown; the list scrolls just enough to show the selected one.
+  fn offset(&self) -> usize {
+    scroll_to(0, self.content_rect().height as usize, self.selected)
+  }
+}
+
+impl Draw for SelectBox {
+  fn size(&self) -> Size {
+    Size::new(self.width, self.height)
+  }
+
+  fn is_dirty(&self) -> bool {
+    self.dirty
+  }
+
+  fn mark_clean(&mut self) {
+    self.dirty = false;
+  }
+
+  fn restyle(&mut self, theme: &Theme) {
+    self.restyle_with(theme);
+  }
+
+  fn as_accessible(&self) -> Option<&dyn Accessible> {
+    Some(self)
+  }
+
+  fn draw(&self, canvas: &mut Canvas) {
+    let style = self.computed_style();
+    style.paint(canvas, self.size(), |canvas| {
+      style.draw_frame(canvas, self.size(), self.state.focused);
+      let content = self.content_rect();
+      let offset = self.offset();
+      canvas.within(content, |canvas| {
+        let shown = self.options.iter().enumerate().skip(offset);
+        for (y, (i, option)) in shown.take(content.height as usize).enumerate() {
+          if i == self.selected {
+            canvas.put(0, y as u16, '>');
+          }
+          canvas.text(1, y as u16, option);
+        }
+      });
+    });
+  }
+
+  fn as_interactive(&mut self) -> Option<&mut dyn Interactive> {
+    Some(self)
+  }
+}
+
+impl Interactive for SelectBox {
+  fn handle_event(&mut self, event: &Event) -> bool {
+    let content = self.content_rect();
+    match event {
+      Event::Click { y, .. } if *y >= content.y && *y < content.bottom() => {
+        self.select(self.offset() + (*y - content.y) as usize)
+      }
+      Event::Key(Key::Up) if self.selected > 0 => self.select(self.selected - 1),
+      Event::Key(Key::Down) => self.select(self.selected + 1),
+      Event::Key(Key::Home) => self.select(0),
+      Event::Key(Key::End) => self.select(self.options.len().saturating_sub(1)),
+      _ => false,
+    }
+  }
+
+  fn set_focused(&mut self, focused: bool) {
+    self.set_state(WidgetState {
+      focused,
+      ..self.state
+    });
+  }
+
+  fn set_hovered(&mut self, hovered: bool) {
+    self.set_state(WidgetState {
+      hovered,
+      ..self.state
+    });
+  }
+
+  fn is_disabled(&self) -> bool {
+    self.state.disabled
+  }
+}
+
+impl Accessible for SelectBox {
+  fn role(&self) -> Role {
+    Role::ListBox
+  }
+
+  fn name(&self) -> Option<&str> {
+    self.accessible_name.as_deref()
+  }
+
+  fn value(&self) -> Option<String> {
+    self.selected_option().map(str::to_string)
+  }
+
+  fn state(&self) -> AccessibleState {
+    self.accessible_state()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::snapshot::golden;
+  use crate::Screen;
+  use std::cell::RefCell;
+  use std::rc::Rc;
+
+  fn options() -> Vec<String> {
+    ["Yes", "Maybe", "No"]
+      .iter()
+      .map(|o| o.to_string())
+      .collect()
+  }
+
+  #[test]
+  fn keys_move_the_selection_and_report_changes() {
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let log = Rc::clone(&changes);
+    let mut select = SelectBox::new(9, 5, options())
+      .on_change(move |i, option| log.borrow_mut().push((i, option.to_string())));
+
+    assert!(select.handle_event(&Event::Key(Key::End)));
+    assert!(!select.handle_event(&Event::Key(Key::Down)));
+    assert!(select.handle_event(&Event::Key(Key::Up)));
+    assert_eq!(
+      vec![(2, "No".to_string()), (1, "Maybe".to_string())],
+      *changes.borrow()
+    );
+    assert_eq!(Some("Maybe"), select.selected_option());
+  }
+
+  #[test]
+  fn list_scrolls_to_the_selected_option() {
+    let mut select = SelectBox::new(9, 4, options());
+    select.handle_event(&Event::Key(Key::End));
+
+    let mut canvas = Canvas::new(9, 4);
+    select.draw(&mut canvas);
+    assert_eq!(
+      "┌───────┐\n│ Maybe │\n│>No    │\n└───────┘\n",
+      canvas.to_string()
+    );
+
+    // a click on the first row picks the first option shown
+    assert!(select.handle_event(&Event::Click { x: 3, y: 1 }));
+    assert_eq!(1, select.selected());
+  }
+
+  #[test]
+  fn select_box_snapshots() {
+    let mut screen = Screen::new(vec![
+      Box::new(SelectBox::new(10, 5, options())),
+      Box::new(SelectBox::new(10, 4, options())),
+    ]);
+    // the second box only has room for two options, so selecting the last one scrolls it
+    screen.handle_event(Event::FocusNext);
+    screen.handle_event(Event::FocusNext);
+    screen.handle_event(Event::Key(Key::Down));
+    screen.handle_event(Event::Key(Key::Down));
+    golden().assert_text("select_box", &screen);
+  }
+}
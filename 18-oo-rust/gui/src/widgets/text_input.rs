@@ -0,0 +1,280 @@
+use super::impl_widget;
+use crate::style::{standard_styles, StateStyles};
+use crate::{
+  Accessible, AccessibleState, Canvas, Draw, Event, Interactive, Key, Role, Size, Style, Theme,
+  WidgetState,
+};
+
+// Called with the new value.
+type ChangeCallback = Box<dyn FnMut(&str)>;
+
+// A single line of editable text. The focused input shows its cursor as a `|` between the
+// characters, and scrolls horizontally to keep the cursor in view.
+pub struct TextInput {
+  pub width: u16,
+  pub height: u16,
+  pub style: Style,
+  pub accessible_name: Option<String>,
+  dirty: bool,
+  // the number of characters the input takes at most, if it's limited
+  pub max_length: Option<usize>,
+  value: Vec<char>,
+  // the cursor is in front of the character at this index
+  cursor: usize,
+  state: WidgetState,
+  // what the theme has for text inputs
+  themed: StateStyles,
+  on_change: Option<ChangeCallback>,
+}
+
+impl_widget!(TextInput);
+
+impl TextInput {
+  pub fn new(width: u16, height: u16) -> TextInput {
+    TextInput {
+      width,
+      height,
+      style: Style::default(),
+      accessible_name: None,
+      dirty: true,
+      max_length: None,
+      value: Vec::new(),
+      cursor: 0,
+      state: WidgetState::default(),
+      themed: standard_styles("TextInput"),
+      on_change: None,
+    }
+  }
+
+  pub fn with_max_length(mut self, max_length: usize) -> TextInput {
+    self.max_length = Some(max_length);
+    self.value.truncate(max_length);
+    self.cursor = self.cursor.min(self.value.len());
+    self
+  }
+
+  // Registers the callback run with the new value whenever the text is edited.
+  pub fn on_change(mut self, callback: impl FnMut(&str) + 'static) -> TextInput {
+    self.on_change = Some(Box::new(callback));
+    self
+  }
+
+  pub fn value(&self) -> String {
+    self.value.iter().collect()
+  }
+
+  // Replaces the text (cut off at the maximum length), with the cursor at its end.
+  pub fn set_value(&mut self, value: &str) {
+    self.value = value.chars().collect();
+    if let Some(max_length) = self.max_length {
+      self.value.truncate(max_length);
+    }
+    self.cursor = self.value.len();
+    self.dirty = true;
+  }
+
+  pub fn cursor(&self) -> usize {
+    self.cursor
+  }
+
+  pub fn insert(&mut self, c: char) -> bool {
+    if self.max_length.is_some_and(|max| self.value.len() >= max) {
+      return false;
+    }
+    self.value.insert(self.cursor, c);
+    self.cursor += 1;
+    self.changed()
+  }
+
+  fn changed(&mut self) -> bool {
+    self.dirty = true;
+    if let Some(callback) = self.on_change.as_mut() {
+      callback(&self.value.iter().collect::<String>());
+    }
+    true
+  }
+
+  fn move_cursor(&mut self, to: usize) -> bool {
+    let to = to.min(self.value.len());
+    let moved = to != self.cursor;
+    self.cursor = to;
+    self.dirty |= moved;
+    moved
+  }
+
+  // The index of the first character shown, so that the cursor (which takes a cell of its own
+  // while focused) stays in view.
+  fn offset(&self) -> usize {
+    let width = self.content_rect().width as usize;
+    let needed = self.cursor + usize::from(self.state.focused);
+    needed.saturating_sub(width)
+  }
+}
+
+impl Draw for TextInput {
+  fn size(&self) -> Size {
+    Size::new(self.width, self.height)
+  }
+
+  fn is_dirty(&self) -> bool {
+    self.dirty
+  }
+
+  fn mark_clean(&mut self) {
+    self.dirty = false;
+  }
+
+  fn restyle(&mut self, theme: &Theme) {
+    self.restyle_with(theme);
+  }
+
+  fn as_accessible(&self) -> Option<&dyn Accessible> {
+    Some(self)
+  }
+
+  fn draw(&self, canvas: &mut Canvas) {
+    let style = self.computed_style();
+    style.paint(canvas, self.size(), |canvas| {
+      style.draw_frame(canvas, self.size(), self.state.focused);
+      let mut shown = self.value.clone();
+      if self.state.focused {
+        shown.insert(self.cursor, '|');
+      }
+      let text: String = shown[self.offset()..].iter().collect();
+      canvas.within(self.content_rect(), |canvas| canvas.text(0, 0, &text));
+    });
+  }
+
+  fn as_interactive(&mut self) -> Option<&mut dyn Interactive> {
+    Some(self)
+  }
+}
+
+impl Interactive for TextInput {
+  fn handle_event(&mut self, event: &Event) -> bool {
+    match event {
+      Event::Click { x, .. } => {
+        let column = x.saturating_sub(self.content_rect().x) as usize;
+        self.move_cursor(self.offset() + column)
+      }
+      Event::Key(Key::Char(c)) => self.insert(*c),
+      Event::Key(Key::Space) => self.insert(' '),
+      Event::Key(Key::Backspace) if self.cursor > 0 => {
+        self.cursor -= 1;
+        self.value.remove(self.cursor);
+        self.changed()
+      }
+      Event::Key(Key::Delete) if self.cursor < self.value.len() => {
+        self.value.remove(self.cursor);
+        self.changed()
+      }
+      Event::Key(Key::Left) => self.move_cursor(self.cursor.saturating_sub(1)),
+      Event::Key(Key::Right) => self.move_cursor(self.cursor + 1),
+      Event::Key(Key::Home) => self.move_cursor(0),
+      Event::Key(Key::End) => self.move_cursor(self.value.len()),
+      _ => false,
+    }
+  }
+
+  fn set_focused(&mut self, focused: bool) {
+    self.set_state(WidgetState {
+      focused,
+      ..self.state
+    });
+  }
+
+  fn set_hovered(&mut self, hovered: bool) {
+    self.set_state(WidgetState {
+      hovered,
+      ..self.state
+    });
+  }
+
+  fn is_disabled(&self) -> bool {
+    self.state.disabled
+  }
+}
+
+// Inputs don't have a label of their own, so they need an accessible name to be told apart.
+impl Accessible for TextInput {
+  fn role(&self) -> Role {
+    Role::TextInput
+  }
+
+  fn name(&self) -> Option<&str> {
+    self.accessible_name.as_deref()
+  }
+
+  fn value(&self) -> Option<String> {
+    Some(self.value())
+  }
+
+  fn state(&self) -> AccessibleState {
+    self.accessible_state()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::snapshot::golden;
+  use crate::Screen;
+
+  fn type_in(input: &mut TextInput, keys: &[Key]) {
+    for key in keys {
+      input.handle_event(&Event::Key(*key));
+    }
+  }
+
+  #[test]
+  fn editing_moves_the_cursor_along() {
+    let mut input = TextInput::new(12, 3);
+    type_in(
+      &mut input,
+      &[Key::Char('s'), Key::Char('l'), Key::Char('d'), Key::Left],
+    );
+    type_in(&mut input, &[Key::Char('a'), Key::End, Key::Backspace]);
+    type_in(&mut input, &[Key::Home, Key::Delete, Key::Char('S')]);
+    assert_eq!("Sla", input.value());
+    assert_eq!(1, input.cursor());
+  }
+
+  #[test]
+  fn max_length_refuses_extra_characters() {
+    let mut input = TextInput::new(12, 3).with_max_length(3);
+    input.set_value("salad");
+    assert_eq!("sal", input.value());
+    assert!(!input.handle_event(&Event::Key(Key::Char('a'))));
+  }
+
+  #[test]
+  fn focused_input_scrolls_to_the_cursor() {
+    let mut input = TextInput::new(7, 3);
+    input.set_value("lunch today");
+    input.set_focused(true);
+
+    let mut canvas = Canvas::new(7, 3);
+    input.draw(&mut canvas);
+    assert_eq!("╔═════╗\n║oday|║\n╚═════╝\n", canvas.to_string());
+
+    // clicking on the `d` puts the cursor in front of it
+    assert!(input.handle_event(&Event::Click { x: 2, y: 1 }));
+    assert_eq!(8, input.cursor());
+  }
+
+  #[test]
+  fn text_input_snapshots() {
+    let mut short = TextInput::new(12, 3);
+    short.set_value("hello");
+    let mut long = TextInput::new(12, 3);
+    long.set_value("a value too long for the input");
+    let mut screen = Screen::new(vec![
+      Box::new(TextInput::new(12, 3)),
+      Box::new(short),
+      Box::new(long),
+    ]);
+    screen.handle_event(Event::FocusPrev);
+    screen.handle_event(Event::Key(Key::Home));
+    golden().assert_text("text_input", &screen);
+  }
+}
@@ -1,4 +1,4 @@
-use blog_nonrust_way::Post;
+use blog_nonrust_way::{Actor, Post, PostStatus};
 
 // Notice that the only type we’re interacting with from the crate is the Post type. This
 // type will use the state pattern and will hold a value that will be one of three state
@@ -9,28 +9,71 @@ use blog_nonrust_way::Post;
 // instance, but they don’t have to manage the state changes directly. Also, users CAN’T
 // make a mistake with the states, like publishing a post before it’s reviewed.
 pub fn run() {
-  let mut post = Post::new();
+  let alice = Actor::author("alice");
+  let bob = Actor::reviewer("bob");
+  let carol = Actor::reviewer("carol");
+
+  let mut post = Post::new(&alice);
   assert_eq!("", post.content());
   println!("Blog post content after creation:\n{}", post.content());
 
-  post.add_text("I ate a salad for lunch today");
+  post
+    .add_text(&alice, "I ate a salad for lunch today")
+    .unwrap();
   assert_eq!("", post.content());
   println!("Blog post content after adding text:\n{}", post.content());
 
   // users CAN’T make a mistake with the states, like publishing a post before it’s reviewed.
-  post.approve();
+  assert!(post.approve(&bob).is_err());
   assert_eq!("", post.content());
   println!("Blog post content after approval:\n{}", post.content());
 
   // only here, we're asking for a review
-  post.request_review();
+  post.request_review(&alice).unwrap();
   assert_eq!("", post.content());
   println!(
     "Blog post content after review request:\n{}",
     post.content()
   );
 
-  post.approve();
+  // ...and the author can't approve their own post either
+  assert!(post.approve(&alice).is_err());
+  post.approve(&bob).unwrap();
   assert_eq!("I ate a salad for lunch today", post.content());
   println!("Blog post content after approval:\n{}", post.content());
+
+  // a post may require sign-off from more than one reviewer, and may get rejected on the way
+  let mut post = Post::with_required_approvals(&alice, 2);
+  post
+    .add_text(&alice, "I ate a pizza for dinner today")
+    .unwrap();
+  post.request_review(&alice).unwrap();
+  post.approve(&bob).unwrap();
+  post.reject(&carol).unwrap();
+  assert_eq!("", post.content());
+  println!("Blog post content after rejection:\n{}", post.content());
+
+  post.request_review(&alice).unwrap();
+  post.approve(&bob).unwrap();
+  assert!(post.approve(&bob).is_err()); // the same reviewer can't approve twice
+  assert_eq!("", post.content());
+  post.approve(&carol).unwrap();
+  assert_eq!("I ate a pizza for dinner today", post.content());
+  println!(
+    "Blog post content after {} approvals:\n{}",
+    post.approvals().len(),
+    post.content()
+  );
+
+  // text can only be added while the post is a draft
+  if let Err(e) = post.add_text(&alice, " and a cake") {
+    println!("\nCan't add text: {}", e);
+  }
+
+  // the post's status and the way it got there can be inspected at any time
+  assert_eq!(PostStatus::Published, post.status());
+  println!("\nBlog post is {:?}, its history:", post.status());
+  for t in post.history() {
+    println!("  #{} {:?} -> {:?} ({})", t.seq, t.from, t.to, t.action);
+  }
 }
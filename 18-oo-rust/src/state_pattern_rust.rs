@@ -1,4 +1,4 @@
-use blog_rust_way::Post;
+use blog_rust_way::{Actor, Post};
 
 // Encoding States and Behavior as Types
 // ---------------------------------------
@@ -16,13 +16,18 @@ use blog_rust_way::Post;
 // checking that happens at compile time! This ensures that certain bugs, such as display of
 // the content of an unpublished post, will be discovered before they make it to production.
 pub fn run() {
-  let mut post = Post::new();
+  let alice = Actor::author("alice");
+  let bob = Actor::reviewer("bob");
+
+  let mut post = Post::new(&alice);
 
   // ## Compiler Error: method not available on the DraftPost
   // assert_eq!("", post.content());
   // println!("Blog post content after creation:\n{}", post.content());
 
-  post.add_text("I ate a salad for lunch today");
+  post
+    .add_text(&alice, "I ate a salad for lunch today")
+    .unwrap();
 
   // ## Compiler Error: method not available on the DraftPost
   // assert_eq!("", post.content());
@@ -32,15 +37,40 @@ pub fn run() {
   // post.approve();
 
   // ## first, we're only able to ask for a review -> PendingReviewPost
-  let post = post.request_review();
+  // ## every transition is done by someone, who may be refused (and handed the post back)
+  let post = post.request_review(&alice).unwrap();
 
   // ## Compiler Error: method not available on the PendingReviewPost
   // assert_eq!("", post.content());
 
   // ## only then we're able to approve/publish the post -> Post
-  let post = post.approve();
+  let post = match post.approve(&alice) {
+    Ok(_) => panic!("the author must not approve their own post"),
+    Err(refused) => {
+      println!("Approval refused: {}", refused);
+      refused.into_post()
+    }
+  };
+  let post = post.approve(&bob).unwrap();
 
   // ## And, finally, we have the content() method available to get the content out
   assert_eq!("I ate a salad for lunch today", post.content());
   println!("Blog post content after approval:\n{}", post.content());
+
+  // ## Compiler Error: method not available on the (published) Post
+  // post.add_text(&alice, " and a soup");
+
+  // ## a published post has to be turned into a new draft to be revised
+  let mut post = post.revise(&alice).unwrap();
+  post.add_text(&alice, " and a soup").unwrap();
+  let post = post.request_review(&alice).unwrap().approve(&bob).unwrap();
+  assert_eq!("I ate a salad for lunch today and a soup", post.content());
+  println!("Blog post content after revision:\n{}", post.content());
+
+  // ## every transition snapshots the content, so revisions can be compared
+  let revisions = post.revisions().list();
+  println!(
+    "\nChanges between the first and the last revision:\n{}",
+    post.revisions().diff(1, revisions.len()).unwrap()
+  );
 }
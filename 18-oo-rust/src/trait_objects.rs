@@ -1,40 +1,39 @@
-use gui::{Button, Draw, Screen};
+use gui::{Button, Event, Key, Screen, SelectBox};
 use std::thread;
 use std::time::Duration;
 
-struct SelectBox {
-  width: u16,
-  height: u16,
-  options: Vec<String>,
-}
-
-impl Draw for SelectBox {
-  fn draw(&self) {
-    println!("Drawing a select box");
-  }
-}
-
 pub fn run() {
-  let screen = Screen::new(vec![
-    Box::new(Button {
-      width: 16,
-      height: 8,
-      label: String::from("Hello button"),
-    }),
-    Box::new(SelectBox {
-      width: 16,
-      height: 12,
-      options: vec![],
-    }),
+  let mut screen = Screen::new(vec![
+    Box::new(Button::new(16, 8, "Hello button").on_click(|| println!("Hello button clicked"))),
+    Box::new(
+      SelectBox::new(
+        16,
+        12,
+        vec![
+          String::from("Yes"),
+          String::from("Maybe"),
+          String::from("No"),
+        ],
+      )
+      .on_change(|_, option| println!("Selected {}", option)),
+    ),
   ]);
 
   println!("Drawing screen...");
-  screen.draw();
+  screen.render();
+  print!("{}", screen.frame().unwrap());
   println!("Screen drawn\n");
 
   thread::sleep(Duration::from_millis(320));
 
-  println!("Rerawing screen...");
-  screen.draw();
-  println!("Screen redrawn");
+  // a click on the button, then on the select box's second option, then a key down in it
+  screen.handle_event(Event::Click { x: 4, y: 4 });
+  screen.handle_event(Event::Click { x: 4, y: 10 });
+  screen.handle_event(Event::Key(Key::Down));
+
+  // only the components that changed get redrawn
+  println!("Redrawing screen...");
+  let updates = screen.render();
+  print!("{}", screen.frame().unwrap());
+  println!("Screen redrawn, {} cells updated", updates.len());
 }
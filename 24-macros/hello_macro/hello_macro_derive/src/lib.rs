@@ -9,6 +9,8 @@
 // The proc_macro crate comes with Rust, so we didn’t need to add that to the dependencies in
 // Cargo.toml. The proc_macro crate is the compiler’s API that allows us to read and manipulate
 // Rust code from our code.
+mod typestate;
+
 use proc_macro::TokenStream;
 use quote::quote;
 
@@ -62,3 +64,16 @@ fn impl_hello_macro(ast: &syn::DeriveInput) -> TokenStream {
   };
   gen.into() // ...and, we need to convert qoute! output to a TokenStream
 }
+
+// A derive macro doesn't have to implement a trait: this one generates the state types of the
+// blog post from chapter 17 (see typestate.rs). The `attributes(transition)` part registers the
+// #[transition(..)] helper attribute, which would be an unknown attribute otherwise. Rather than
+// panicking on bad input, errors are turned into compile_error! invocations pointing at the
+// offending tokens.
+#[proc_macro_derive(TypeState, attributes(transition))]
+pub fn type_state_derive(input: TokenStream) -> TokenStream {
+  let ast = syn::parse_macro_input!(input as syn::DeriveInput);
+  typestate::expand(&ast)
+    .unwrap_or_else(syn::Error::into_compile_error)
+    .into()
+}
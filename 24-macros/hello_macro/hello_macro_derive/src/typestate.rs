@@ -0,0 +1,169 @@
+// #[derive(TypeState)] turns one struct holding a post's data into one struct per state, e.g.
+//
+//    #[derive(TypeState)]
+//    #[transition(from = Draft, to = PendingReview, via = request_review)]
+//    #[transition(from = PendingReview, to = Published, via = approve)]
+//    struct Post {
+//      content: String,
+//    }
+//
+// generates DraftPost, PendingReviewPost and PublishedPost, each with the fields of Post, plus
+// `DraftPost::request_review(self) -> PendingReviewPost` and `PendingReviewPost::approve(self)
+// -> PublishedPost`. The transitions consume the post, so there's no way to hold on to the old
+// state, and a state without a transition simply doesn't have the method: calling approve on a
+// DraftPost doesn't compile. A post starts in the `from` state of the first transition, which
+// is the only state that can be created from the annotated struct (through From/Into).
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, Ident};
+
+struct Transition {
+  from: Ident,
+  to: Ident,
+  via: Ident,
+}
+
+pub fn expand(ast: &DeriveInput) -> syn::Result<TokenStream> {
+  let name = &ast.ident;
+  let vis = &ast.vis;
+  if !ast.generics.params.is_empty() {
+    return Err(syn::Error::new_spanned(
+      &ast.generics,
+      "TypeState can't be derived for generic types",
+    ));
+  }
+  let fields = match &ast.data {
+    Data::Struct(data) => match &data.fields {
+      Fields::Named(fields) => fields,
+      _ => {
+        return Err(syn::Error::new_spanned(
+          name,
+          "TypeState can only be derived for structs with named fields",
+        ))
+      }
+    },
+    _ => {
+      return Err(syn::Error::new_spanned(
+        name,
+        "TypeState can only be derived for structs",
+      ))
+    }
+  };
+
+  let transitions = parse_transitions(ast)?;
+  let Some(first) = transitions.first() else {
+    return Err(syn::Error::new_spanned(
+      name,
+      "TypeState needs at least one #[transition(from = .., to = .., via = ..)] attribute",
+    ));
+  };
+
+  // the states in order of appearance, each one generating a struct
+  let mut states: Vec<&Ident> = Vec::new();
+  for transition in transitions.iter() {
+    for state in [&transition.from, &transition.to] {
+      if !states.contains(&state) {
+        states.push(state);
+      }
+    }
+  }
+
+  let state_type = |state: &Ident| format_ident!("{}{}", state, name);
+  let field_defs = &fields.named;
+  let field_names: Vec<&Ident> = fields
+    .named
+    .iter()
+    .filter_map(|f| f.ident.as_ref())
+    .collect();
+
+  let structs = states.iter().map(|state| {
+    let ty = state_type(state);
+    let doc = format!("`{}` in the `{}` state.", name, state);
+    let state_name = state.to_string();
+    quote! {
+      #[doc = #doc]
+      #vis struct #ty {
+        #field_defs
+      }
+
+      impl #ty {
+        pub const STATE: &'static str = #state_name;
+      }
+    }
+  });
+
+  let methods = transitions.iter().map(|transition| {
+    let from = state_type(&transition.from);
+    let to = state_type(&transition.to);
+    let via = &transition.via;
+    quote! {
+      impl #from {
+        #vis fn #via(self) -> #to {
+          #to {
+            #(#field_names: self.#field_names),*
+          }
+        }
+      }
+    }
+  });
+
+  let initial = state_type(&first.from);
+  Ok(quote! {
+    #(#structs)*
+    #(#methods)*
+
+    impl ::core::convert::From<#name> for #initial {
+      fn from(post: #name) -> #initial {
+        #initial {
+          #(#field_names: post.#field_names),*
+        }
+      }
+    }
+  })
+}
+
+fn parse_transitions(ast: &DeriveInput) -> syn::Result<Vec<Transition>> {
+  let mut transitions: Vec<Transition> = Vec::new();
+  for attr in ast.attrs.iter().filter(|a| a.path().is_ident("transition")) {
+    let (mut from, mut to, mut via) = (None, None, None);
+    attr.parse_nested_meta(|meta| {
+      let slot = if meta.path.is_ident("from") {
+        &mut from
+      } else if meta.path.is_ident("to") {
+        &mut to
+      } else if meta.path.is_ident("via") {
+        &mut via
+      } else {
+        return Err(meta.error("expected `from`, `to` or `via`"));
+      };
+      if slot.is_some() {
+        return Err(meta.error("duplicate key"));
+      }
+      *slot = Some(meta.value()?.parse::<Ident>()?);
+      Ok(())
+    })?;
+
+    let missing = |key| syn::Error::new_spanned(attr, format!("transition is missing `{}`", key));
+    let transition = Transition {
+      from: from.ok_or_else(|| missing("from"))?,
+      to: to.ok_or_else(|| missing("to"))?,
+      via: via.ok_or_else(|| missing("via"))?,
+    };
+
+    // a state can't have two methods of the same name
+    if transitions
+      .iter()
+      .any(|t| t.from == transition.from && t.via == transition.via)
+    {
+      return Err(syn::Error::new_spanned(
+        &transition.via,
+        format!(
+          "`{}` already has a transition called `{}`",
+          transition.from, transition.via
+        ),
+      ));
+    }
+    transitions.push(transition);
+  }
+  Ok(transitions)
+}
@@ -1,5 +1,5 @@
 use hello_macro::HelloMacro;
-use hello_macro_derive::HelloMacro;
+use hello_macro_derive::{HelloMacro, TypeState};
 
 #[derive(HelloMacro)]
 struct Pancakes;
@@ -7,7 +7,43 @@ struct Pancakes;
 #[derive(HelloMacro)]
 struct Ananas;
 
+// The blog post workflow of chapter 17, where DraftPost, PendingReviewPost and PublishedPost
+// (and their transitions) are generated instead of being written out by hand.
+#[derive(TypeState)]
+#[transition(from = Draft, to = PendingReview, via = request_review)]
+#[transition(from = PendingReview, to = Published, via = approve)]
+#[transition(from = PendingReview, to = Draft, via = reject)]
+struct Post {
+  content: String,
+}
+
+// The generated types are ordinary structs, so we can add our own methods to them.
+impl DraftPost {
+  fn add_text(&mut self, text: &str) {
+    self.content.push_str(text);
+  }
+}
+
+impl PublishedPost {
+  fn content(&self) -> &str {
+    &self.content
+  }
+}
+
 pub fn run() {
   Pancakes::hello_macro();
   Ananas::hello_macro();
+
+  let mut post: DraftPost = Post {
+    content: String::new(),
+  }
+  .into();
+  post.add_text("I ate a salad for lunch today");
+
+  // post.approve(); -- doesn't compile, a draft has to be reviewed first
+  let post = post.request_review().reject();
+  println!("Rejected, the post is a {} again", DraftPost::STATE);
+
+  let post = post.request_review().approve();
+  println!("{}: {}", PublishedPost::STATE, post.content());
 }
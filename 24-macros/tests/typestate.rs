@@ -0,0 +1,48 @@
+use hello_macro_derive::TypeState;
+
+#[derive(TypeState)]
+#[transition(from = Draft, to = PendingReview, via = request_review)]
+#[transition(from = PendingReview, to = Published, via = approve)]
+#[transition(from = PendingReview, to = Draft, via = reject)]
+pub struct Post {
+  pub content: String,
+  pub author: String,
+}
+
+fn draft() -> DraftPost {
+  Post {
+    content: "I ate a salad for lunch today".to_string(),
+    author: "alice".to_string(),
+  }
+  .into()
+}
+
+#[test]
+fn transitions_carry_the_fields_over() {
+  let post = draft().request_review().approve();
+  assert_eq!("I ate a salad for lunch today", post.content);
+  assert_eq!("alice", post.author);
+}
+
+#[test]
+fn states_can_be_revisited() {
+  let mut post = draft().request_review().reject();
+  post.content.push_str(", again");
+  let post = post.request_review().approve();
+  assert_eq!("I ate a salad for lunch today, again", post.content);
+}
+
+#[test]
+fn states_know_their_names() {
+  assert_eq!("Draft", DraftPost::STATE);
+  assert_eq!("PendingReview", PendingReviewPost::STATE);
+  assert_eq!("Published", PublishedPost::STATE);
+}
+
+// Each file in tests/ui must fail to compile with the error in the .stderr file next to it.
+// Run with TRYBUILD=overwrite to update the .stderr files after changing the macro.
+#[test]
+fn invalid_transitions_dont_compile() {
+  let t = trybuild::TestCases::new();
+  t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,17 @@
+use hello_macro_derive::TypeState;
+
+#[derive(TypeState)]
+#[transition(from = Draft, to = PendingReview, via = request_review)]
+#[transition(from = PendingReview, to = Published, via = approve)]
+struct Post {
+  content: String,
+}
+
+fn main() {
+  let post: DraftPost = Post {
+    content: String::new(),
+  }
+  .into();
+  // a draft has to be reviewed before it can be approved
+  post.approve();
+}
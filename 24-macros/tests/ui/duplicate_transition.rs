@@ -0,0 +1,10 @@
+use hello_macro_derive::TypeState;
+
+#[derive(TypeState)]
+#[transition(from = Draft, to = PendingReview, via = submit)]
+#[transition(from = Draft, to = Published, via = submit)]
+struct Post {
+  content: String,
+}
+
+fn main() {}
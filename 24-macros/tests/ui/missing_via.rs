@@ -0,0 +1,9 @@
+use hello_macro_derive::TypeState;
+
+#[derive(TypeState)]
+#[transition(from = Draft, to = PendingReview)]
+struct Post {
+  content: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use hello_macro_derive::TypeState;
+
+#[derive(TypeState)]
+#[transition(from = Draft, to = PendingReview, via = request_review)]
+enum Post {
+  Draft,
+}
+
+fn main() {}
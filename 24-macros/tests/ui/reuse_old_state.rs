@@ -0,0 +1,17 @@
+use hello_macro_derive::TypeState;
+
+#[derive(TypeState)]
+#[transition(from = Draft, to = PendingReview, via = request_review)]
+struct Post {
+  content: String,
+}
+
+fn main() {
+  let post: DraftPost = Post {
+    content: String::new(),
+  }
+  .into();
+  let _pending = post.request_review();
+  // the transition consumed the draft
+  println!("{}", post.content);
+}
@@ -0,0 +1,16 @@
+use hello_macro_derive::TypeState;
+
+#[derive(TypeState)]
+#[transition(from = Draft, to = PendingReview, via = request_review)]
+#[transition(from = PendingReview, to = Published, via = approve)]
+struct Post {
+  content: String,
+}
+
+fn main() {
+  // only a draft can be made from a Post
+  let _post: PublishedPost = Post {
+    content: String::new(),
+  }
+  .into();
+}